@@ -0,0 +1,81 @@
+use crate::memory::{Address, ModuleInfo};
+use std::io::{self, ErrorKind, Read, Write};
+use std::path::PathBuf;
+
+const MAX_STRING_LEN: usize = 0x1000;
+
+pub fn write_header(writer: &mut impl Write, magic: &[u8; 4], version: u32) -> io::Result<()> {
+    writer.write_all(magic)?;
+    writer.write_all(&version.to_le_bytes())
+}
+
+pub fn read_header(
+    reader: &mut impl Read,
+    magic: &[u8; 4],
+    version: u32,
+    kind: &str,
+) -> io::Result<()> {
+    let mut header = [0u8; 8];
+    reader.read_exact(&mut header)?;
+    if &header[..4] != magic || header[4..] != version.to_le_bytes() {
+        return Err(invalid_data(format!("not a {}", kind)));
+    }
+    Ok(())
+}
+
+pub fn write_u64(writer: &mut impl Write, value: u64) -> io::Result<()> {
+    writer.write_all(&value.to_le_bytes())
+}
+
+pub fn read_u64(reader: &mut impl Read) -> io::Result<u64> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+pub fn write_string(writer: &mut impl Write, value: &str) -> io::Result<()> {
+    write_u64(writer, value.len() as u64)?;
+    writer.write_all(value.as_bytes())
+}
+
+pub fn read_string(reader: &mut impl Read) -> io::Result<String> {
+    let len = read_u64(reader)? as usize;
+    if len > MAX_STRING_LEN {
+        return Err(invalid_data("string too long"));
+    }
+    let mut bytes = vec![0u8; len];
+    reader.read_exact(&mut bytes)?;
+    String::from_utf8(bytes).map_err(invalid_data)
+}
+
+pub fn write_modules(writer: &mut impl Write, modules: &[ModuleInfo]) -> io::Result<()> {
+    write_u64(writer, modules.len() as u64)?;
+    for module in modules {
+        write_string(writer, &module.name)?;
+        write_u64(writer, module.base as u64)?;
+        write_u64(writer, module.size as u64)?;
+        write_string(writer, &module.path.to_string_lossy())?;
+    }
+    Ok(())
+}
+
+pub fn read_modules(reader: &mut impl Read) -> io::Result<Vec<ModuleInfo>> {
+    let count = read_u64(reader)?;
+    let mut modules = Vec::new();
+    for _ in 0..count {
+        modules.push(ModuleInfo {
+            name: read_string(reader)?,
+            base: read_u64(reader)? as Address,
+            size: read_u64(reader)? as usize,
+            path: PathBuf::from(read_string(reader)?),
+        });
+    }
+    Ok(modules)
+}
+
+pub fn invalid_data<E>(error: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::new(ErrorKind::InvalidData, error)
+}
@@ -0,0 +1,924 @@
+use crate::detector::{DetectorKind, DetectorSettings};
+use crate::fisher::{self, FisherConfig};
+use crate::hook::HookReader;
+use crate::memory::{
+    self, find_processes, get_pid, Address, MemoryError, MemoryReader, MemoryRegion, Minidump,
+    ModuleInfo, ProcessMemory, RegionState, Snapshot, Vec3,
+};
+use crate::offsets::{self, GameBuild, OffsetDatabase};
+use crate::pointer_scan::{self, PointerMap, ScanOptions};
+use crate::remote;
+use crate::rtti::{self, RttiClass};
+use crate::signature::{ModuleImage, Signature, StaticPointer};
+use crate::trace::{CastRecord, ReplaySummary, Trace};
+use crate::tune;
+use crate::value_scan::{Filter, ValueScanner, ValueType};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, BufRead};
+use std::mem::size_of;
+use std::time::Duration;
+
+const USAGE: &str = "usage: mcbe-fisher-rs <command> [options]
+
+commands:
+  sigscan <pattern>    scan a module image for a byte pattern (?? = wildcard)
+      --dump <file>        scan a raw module dump instead of the live process
+      --base <address>     load address of the dump (default 0x140000000)
+      --module <name>      module to scan (default: the game module)
+      --operand <offset>   resolve a rip-relative operand at this offset of the match
+      --length <bytes>     instruction length used for rip-relative resolution
+  processes            list running game instances
+  modules              list the modules loaded by the game process
+  regions              list the memory regions of the game process
+      --all                include free and reserved regions
+  peek <address>       decode the memory at an address
+      --as <type>          u8, u16, u32, u64, i32, i64, f32, f64, pointer, vec3, string,
+                           vector, map or shared_ptr (default pointer)
+      --element <bytes>    element size of a vector (default 8)
+      --bucket <n>         only list the entries in one bucket of a map
+  rtti [class]         find the type descriptor and vtables of a class from its RTTI
+                       (lists every class when no name is given)
+      --module <name>      module to scan (default: the game module)
+      --objects            also list the live objects that use the class vtable
+      --max-results <n>    stop after this many objects (default 100)
+  hook                 find the fishing hook actor and print its position, motion and water state
+  offsets              show the running game build and the offsets entry it matches
+  trace                follow the rod-state pointer chain and print every hop
+  valuescan [value]    find an address by scanning for a value and filtering the results
+      --type <type>        u8, u16, u32, u64, i32, i64, f32 or f64 (default u32)
+  snapshot <file>      save the rod-state chain and the memory around it to a file
+      --radius <bytes>     bytes captured around every pointer in the chain (default 0x1000)
+      --full               capture every readable region instead
+  pointerscan <address>  find static pointer chains that lead to an address
+      --depth <n>          maximum number of hops (default 5)
+      --max-offset <bytes> largest offset tried at each hop (default 0x1000)
+      --max-results <n>    stop after this many chains (default 100)
+      --module <name>      only accept chains based in this module
+      --map <file>         scan a saved pointer map instead of the live process
+      --save-map <file>    save the pointer map of the live process
+      --output <file>      write the chains as an offsets pack
+  replay <trace>...    feed recorded traces through a bite detector and compare where it
+                       would reel with the bites labelled in the trace
+      --profile <file>     start from a saved detector profile
+      --detector <name>    peak-fall, edge, moving-average or change-point (default peak-fall)
+      --threshold <n>      peak-fall delay or change-point threshold
+      --stability <n>      peak-fall: unchanged samples after the peak that count as a bite
+      --min-peak <n>       smallest peak that can be a bite
+      --falling <n>        peak-fall: falling samples ignored after the peak
+      --min-rate <n>       edge: fall per second that counts as a bite
+      --confirm <n>        edge: falling samples needed in a row
+      --window <n>         moving-average: number of samples averaged
+      --rise <n>           moving-average: rise that arms the detector
+      --fall <n>           moving-average: fall from the peak that reels in
+      --drift <n>          change-point: drop ignored per sample
+      --tolerance <ms>     how late a reel still counts as a hit (default 1000)
+      --strict             fail unless every cast was handled correctly
+  tune <trace>...      search the detector settings for the ones that best match the
+                       labelled bites and save them to fisher.toml
+      --detector <name>    only tune this detector (default: all of them)
+      --tolerance <ms>     how late a reel still counts as a hit (default 1000)
+      --top <n>            number of configurations to print (default 10, 0 = all)
+      --output <file>      save the best settings as a detector profile for replay instead
+  help                 show this message
+
+commands that read the live game also accept:
+  --process <name>     process to attach to (default: the game executable)
+  --pid <pid>          attach to a specific instance instead of the first one
+  --snapshot <file>    read a saved snapshot instead of the live game
+  --minidump <file>    read a windows minidump (.dmp) instead of the live game";
+
+const SNAPSHOT_RADIUS: usize = 0x1000;
+
+const VALUE_SCAN_HELP: &str = "filters: changed, unchanged, increased, decreased, exact <value>
+other commands: list, new [value], quit
+
+to find the rod state, start with the rod in hand and run valuescan without a value,
+then cast and type changed, wait and type unchanged, reel in and type changed,
+and repeat until only a few candidates are left";
+
+struct Args {
+    positional: Vec<String>,
+    options: HashMap<String, String>,
+}
+
+impl Args {
+    fn parse(args: &[String]) -> Self {
+        let mut positional = Vec::new();
+        let mut options = HashMap::new();
+        let mut iter = args.iter().peekable();
+
+        while let Some(arg) = iter.next() {
+            if let Some(key) = arg.strip_prefix("--") {
+                let value = match iter.peek() {
+                    Some(next) if !next.starts_with("--") => iter.next().unwrap().clone(),
+                    _ => String::new(),
+                };
+                options.insert(key.to_string(), value);
+            } else {
+                positional.push(arg.clone());
+            }
+        }
+
+        Args {
+            positional,
+            options,
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<&str> {
+        self.options.get(key).map(String::as_str)
+    }
+
+    fn address(&self, key: &str) -> Result<Option<Address>, String> {
+        self.get(key).map(parse_address).transpose()
+    }
+
+    fn number(&self, key: &str) -> Result<Option<usize>, String> {
+        self.get(key).map(parse_number).transpose()
+    }
+
+    fn float(&self, key: &str) -> Result<Option<f32>, String> {
+        self.get(key)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|_| format!("invalid number: {}", value))
+            })
+            .transpose()
+    }
+}
+
+pub fn run(args: &[String]) -> Result<(), String> {
+    attach_console();
+
+    let (command, rest) = args.split_first().ok_or(USAGE)?;
+    let args = Args::parse(rest);
+    match command.as_str() {
+        "sigscan" => sigscan(&args),
+        "processes" => list_processes(&args),
+        "modules" => list_modules(&args),
+        "regions" => list_regions(&args),
+        "peek" => peek(&args),
+        "rtti" => rtti_scan(&args),
+        "hook" => show_hook(&args),
+        "offsets" => show_offsets(&args),
+        "trace" => trace(&args),
+        "valuescan" => value_scan(&args),
+        "snapshot" => save_snapshot(&args),
+        "pointerscan" => pointer_scan(&args),
+        "replay" => replay(&args),
+        "tune" => tune_detector(&args),
+        "help" | "--help" | "-h" => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        _ => Err(format!("unknown command: {}\n\n{}", command, USAGE)),
+    }
+}
+
+fn sigscan(args: &Args) -> Result<(), String> {
+    let pattern = args
+        .positional
+        .first()
+        .ok_or("sigscan requires a pattern")?;
+    let signature: Signature = pattern.parse()?;
+
+    let image = match args.get("dump") {
+        Some(path) => {
+            let base = args.address("base")?.unwrap_or(0x1_4000_0000);
+            ModuleImage::from_file(path, base)
+                .map_err(|e| format!("failed to read {}: {}", path, e))?
+        }
+        None => {
+            let module = args.get("module").unwrap_or(memory::DEFAULT_MODULE);
+            let memory = attach(args)?;
+            let module = memory
+                .find_module(module)
+                .map_err(|e| format!("failed to find module {}: {}", module, e.explain()))?;
+            ModuleImage::read(&memory, &module)
+        }
+    };
+    println!(
+        "scanning 0x{:X} bytes at 0x{:X}",
+        image.bytes.len(),
+        image.base
+    );
+
+    match (args.number("operand")?, args.number("length")?) {
+        (Some(operand_offset), Some(instruction_len)) => {
+            let pointer = StaticPointer {
+                signature,
+                operand_offset,
+                instruction_len,
+            };
+            let address = pointer.resolve(&image).ok_or("signature not found")?;
+            println!(
+                "resolved 0x{:X} (module+0x{:X})",
+                address,
+                address.wrapping_sub(image.base)
+            );
+        }
+        (None, None) => {
+            let matches = signature.find_all(&image.bytes);
+            if matches.is_empty() {
+                return Err("signature not found".to_string());
+            }
+            for offset in matches {
+                println!(
+                    "match at 0x{:X} (module+0x{:X})",
+                    image.base + offset,
+                    offset
+                );
+            }
+        }
+        _ => return Err("--operand and --length must be used together".to_string()),
+    }
+    Ok(())
+}
+
+fn list_modules(args: &Args) -> Result<(), String> {
+    let memory = attach(args)?;
+    let modules = memory
+        .modules()
+        .map_err(|e| format!("failed to enumerate modules: {}", e.explain()))?;
+
+    for module in modules {
+        println!(
+            "0x{:016X} 0x{:08X} {:<32} {}",
+            module.base,
+            module.size,
+            module.name,
+            module.path.display()
+        );
+    }
+    Ok(())
+}
+
+fn list_regions(args: &Args) -> Result<(), String> {
+    let memory = attach(args)?;
+    let regions = memory
+        .regions()
+        .map_err(|e| format!("failed to enumerate regions: {}", e.explain()))?;
+    let modules = memory.modules().unwrap_or_default();
+
+    for region in regions {
+        if region.state != RegionState::Committed && args.get("all").is_none() {
+            continue;
+        }
+        let module = modules
+            .iter()
+            .find(|module| (module.base..module.base + module.size).contains(&region.base))
+            .map(|module| module.name.as_str())
+            .unwrap_or_default();
+        println!(
+            "0x{:016X} 0x{:010X} {:<9} {} {:<7} {}",
+            region.base,
+            region.size,
+            format!("{:?}", region.state).to_lowercase(),
+            region.protection,
+            format!("{:?}", region.kind).to_lowercase(),
+            module
+        );
+    }
+    Ok(())
+}
+
+fn peek(args: &Args) -> Result<(), String> {
+    let address = parse_address(args.positional.first().ok_or("peek requires an address")?)?;
+    let memory = attach(args)?;
+    let error = |e: MemoryError| format!("failed to read 0x{:X}: {}", address, e.explain());
+
+    match args.get("as").unwrap_or("pointer") {
+        "u8" => println!("{}", memory.read_le::<u8>(address).map_err(error)?),
+        "u16" => println!("{}", memory.read_le::<u16>(address).map_err(error)?),
+        "u32" => println!("{}", memory.read_le::<u32>(address).map_err(error)?),
+        "u64" => println!("{}", memory.read_le::<u64>(address).map_err(error)?),
+        "i32" => println!("{}", memory.read_le::<i32>(address).map_err(error)?),
+        "i64" => println!("{}", memory.read_le::<i64>(address).map_err(error)?),
+        "f32" => println!("{}", memory.read_le::<f32>(address).map_err(error)?),
+        "f64" => println!("{}", memory.read_le::<f64>(address).map_err(error)?),
+        "pointer" => println!("0x{:X}", memory.read_le::<usize>(address).map_err(error)?),
+        "vec3" => {
+            let vec = memory.read_le::<Vec3>(address).map_err(error)?;
+            println!("{} {} {}", vec.x, vec.y, vec.z);
+        }
+        "string" => println!(
+            "{:?}",
+            remote::read_string(&memory, address).map_err(error)?
+        ),
+        "vector" => {
+            let element_size = args.number("element")?.unwrap_or(size_of::<usize>());
+            let elements =
+                remote::vector_elements(&memory, address, element_size).map_err(error)?;
+            println!("{} elements", elements.len());
+            if element_size == size_of::<usize>() {
+                let values = remote::read_vector::<_, usize>(&memory, address).map_err(error)?;
+                for (element, value) in elements.iter().zip(values) {
+                    println!("0x{:X} = 0x{:X}", element, value);
+                }
+            } else {
+                for element in elements {
+                    println!("0x{:X}", element);
+                }
+            }
+        }
+        "map" => {
+            let entries = match args.number("bucket")? {
+                Some(bucket) => remote::unordered_map_bucket(&memory, address, bucket),
+                None => remote::unordered_map_entries(&memory, address),
+            }
+            .map_err(error)?;
+            println!("{} entries", entries.len());
+            for entry in entries {
+                println!("0x{:X}", entry);
+            }
+        }
+        "shared_ptr" => match remote::read_shared_ptr(&memory, address).map_err(error)? {
+            Some(shared) => println!(
+                "0x{:X} (control block 0x{:X}, {} uses, {} weak)",
+                shared.pointer,
+                shared.control,
+                shared.uses,
+                shared.weaks - 1
+            ),
+            None => println!("null"),
+        },
+        other => return Err(format!("unknown type: {}", other)),
+    }
+    Ok(())
+}
+
+fn rtti_scan(args: &Args) -> Result<(), String> {
+    let module = args.get("module").unwrap_or(memory::DEFAULT_MODULE);
+    let memory = attach(args)?;
+    let module = memory
+        .find_module(module)
+        .map_err(|e| format!("failed to find module {}: {}", module, e.explain()))?;
+    let image = ModuleImage::read(&memory, &module);
+
+    let name = match args.positional.first() {
+        Some(name) => name,
+        None => {
+            let classes = rtti::list_classes(&image);
+            for (name, type_descriptor) in &classes {
+                println!("0x{:X} {}", type_descriptor, rtti::undecorate(name));
+            }
+            println!("{} classes", classes.len());
+            return Ok(());
+        }
+    };
+
+    let class = RttiClass::find(&image, name)
+        .ok_or_else(|| format!("no type descriptor for {} in {}", name, module.name))?;
+    println!(
+        "type descriptor {} at 0x{:X} ({}+0x{:X})",
+        class.name,
+        class.type_descriptor,
+        module.name,
+        class.type_descriptor - module.base
+    );
+    if class.vtables.is_empty() {
+        return Err("no vtables reference this type descriptor".to_string());
+    }
+    for vtable in &class.vtables {
+        println!(
+            "vtable at 0x{:X} ({}+0x{:X}), object locator 0x{:X}, offset 0x{:X}",
+            vtable.address,
+            module.name,
+            vtable.address - module.base,
+            vtable.locator,
+            vtable.offset
+        );
+    }
+
+    if args.get("objects").is_some() {
+        let vtable = class
+            .primary_vtable()
+            .ok_or("class has no primary vtable")?;
+        let max_results = args.number("max-results")?.unwrap_or(100);
+        let objects = rtti::find_objects(&memory, &module, vtable, max_results)
+            .map_err(|e| format!("failed to scan for objects: {}", e.explain()))?;
+        for object in &objects {
+            println!("object at 0x{:X}", object);
+        }
+        println!("{} objects", objects.len());
+    }
+    Ok(())
+}
+
+fn show_hook(args: &Args) -> Result<(), String> {
+    let offsets = OffsetDatabase::load()?;
+    let memory = attach(args)?;
+    let (entry, module) = offsets.resolve(&memory)?;
+    let fields = entry
+        .hook
+        .clone()
+        .ok_or_else(|| format!("offsets entry '{}' has no hook table", entry.name))?;
+    let reader = HookReader::new(&memory, module, fields)?;
+
+    let hooks = reader
+        .find_hooks(&memory)
+        .map_err(|e| format!("failed to scan for hooks: {}", e.explain()))?;
+    if hooks.is_empty() {
+        return Err("no fishing hook found, cast the rod first".to_string());
+    }
+    for hook in hooks {
+        match reader.read(&memory, hook) {
+            Ok(state) => println!(
+                "hook at 0x{:X}: position {} {} {}, motion {} {} {}, in water: {}",
+                hook,
+                state.position.x,
+                state.position.y,
+                state.position.z,
+                state.motion.x,
+                state.motion.y,
+                state.motion.z,
+                state.in_water
+            ),
+            Err(e) => println!("hook at 0x{:X}: {}", hook, e.explain()),
+        }
+    }
+    Ok(())
+}
+
+fn show_offsets(args: &Args) -> Result<(), String> {
+    let database = OffsetDatabase::load()?;
+    let memory = attach(args)?;
+    println!("offset packs: {}", offsets::pack_dir().display());
+
+    for module_name in database.modules() {
+        let module = match memory.find_module(module_name) {
+            Ok(module) => module,
+            Err(e) => {
+                println!("{}: {}", module_name, e);
+                continue;
+            }
+        };
+        let build = GameBuild::read(&memory, &module);
+        println!("{} at 0x{:X}: {}", module_name, module.base, build);
+
+        let entry = match database.select(module_name, &build) {
+            Some(entry) => entry,
+            None => {
+                println!("  no matching entry");
+                continue;
+            }
+        };
+        if entry.is_fallback() {
+            println!("  matches fallback entry '{}'", entry.name);
+        } else {
+            println!("  matches '{}'", entry.name);
+        }
+
+        let base = entry.static_base(&memory, &module);
+        match memory.follow_pointers(base, &entry.offsets) {
+            Ok(address) => println!("  rod state at 0x{:X}", address),
+            Err(e) => println!("  pointer chain is broken: {}", e.explain()),
+        }
+    }
+    Ok(())
+}
+
+fn trace(args: &Args) -> Result<(), String> {
+    let database = OffsetDatabase::load()?;
+    let memory = attach(args)?;
+    let (entry, module) = database.resolve(&memory)?;
+    println!("using offsets '{}'", entry.name);
+
+    let trace = memory.trace_pointers(entry.static_base(&memory, &module), &entry.offsets);
+    print!("{}", trace);
+    match trace.address() {
+        Some(address) => {
+            println!("rod state at 0x{:X}", address);
+            Ok(())
+        }
+        None => Err(trace.failure().unwrap_or_default()),
+    }
+}
+
+fn value_scan(args: &Args) -> Result<(), String> {
+    let value_type: ValueType = args.get("type").unwrap_or("u32").parse()?;
+    let exact = args
+        .positional
+        .first()
+        .map(|value| value_type.parse_value(value))
+        .transpose()?;
+    let memory = attach(args)?;
+    let scan_error = |e: MemoryError| format!("failed to scan: {}", e.explain());
+
+    let mut scanner = ValueScanner::new(value_type);
+    scanner.first_scan(&memory, exact).map_err(scan_error)?;
+    println!("{}", scanner.summary());
+    println!("{}", VALUE_SCAN_HELP);
+
+    for line in io::stdin().lock().lines() {
+        let line = line.map_err(|e| format!("failed to read input: {}", e))?;
+        let command = line.trim();
+        match command.split_whitespace().next() {
+            None => continue,
+            Some("quit" | "exit") => break,
+            Some("list") => print_candidates(&scanner, usize::MAX),
+            Some("new") => {
+                let exact = command
+                    .split_whitespace()
+                    .nth(1)
+                    .map(|value| value_type.parse_value(value))
+                    .transpose();
+                match exact {
+                    Ok(exact) => {
+                        scanner = ValueScanner::new(value_type);
+                        scanner.first_scan(&memory, exact).map_err(scan_error)?;
+                        println!("{}", scanner.summary());
+                    }
+                    Err(e) => println!("{}", e),
+                }
+            }
+            Some(_) => match Filter::parse(command, value_type) {
+                Ok(filter) => {
+                    scanner.next_scan(&memory, filter).map_err(scan_error)?;
+                    println!("{}", scanner.summary());
+                    print_candidates(&scanner, 10);
+                }
+                Err(e) => println!("{}", e),
+            },
+        }
+    }
+    Ok(())
+}
+
+fn print_candidates(scanner: &ValueScanner, limit: usize) {
+    let candidates = scanner.candidates();
+    if candidates.len() > limit {
+        return;
+    }
+    for candidate in candidates {
+        println!("0x{:X} = {}", candidate.address, candidate.value);
+    }
+}
+
+fn save_snapshot(args: &Args) -> Result<(), String> {
+    let path = args
+        .positional
+        .first()
+        .ok_or("snapshot requires a file name")?;
+    let memory = attach(args)?;
+
+    let snapshot = if args.get("full").is_some() {
+        Snapshot::capture_full(&memory)
+    } else {
+        let database = OffsetDatabase::load()?;
+        let (entry, module) = database.resolve(&memory)?;
+        println!("using offsets '{}'", entry.name);
+
+        let trace = memory.trace_pointers(entry.static_base(&memory, &module), &entry.offsets);
+        print!("{}", trace);
+        if let Some(failure) = trace.failure() {
+            println!("{}", failure);
+        }
+        let radius = args.number("radius")?.unwrap_or(SNAPSHOT_RADIUS);
+        Snapshot::capture(&memory, &trace.addresses(), radius)
+    }
+    .map_err(|e| format!("failed to capture snapshot: {}", e.explain()))?;
+
+    snapshot
+        .save(path)
+        .map_err(|e| format!("failed to write {}: {}", path, e))?;
+    println!("saved 0x{:X} bytes to {}", snapshot.size(), path);
+    Ok(())
+}
+
+fn pointer_scan(args: &Args) -> Result<(), String> {
+    let target = args
+        .positional
+        .first()
+        .map(|address| parse_address(address))
+        .transpose()?;
+    if target.is_none() && args.get("save-map").is_none() {
+        return Err("pointerscan requires an address".to_string());
+    }
+
+    let map = match args.get("map") {
+        Some(path) => {
+            PointerMap::load(path).map_err(|e| format!("failed to read {}: {}", path, e))?
+        }
+        None => {
+            let memory = attach(args)?;
+            println!("capturing pointer map");
+            PointerMap::capture(&memory)
+                .map_err(|e| format!("failed to capture pointer map: {}", e.explain()))?
+        }
+    };
+    println!(
+        "{} pointers, {} modules",
+        map.pointer_count(),
+        map.modules.len()
+    );
+
+    if let Some(path) = args.get("save-map") {
+        map.save(path)
+            .map_err(|e| format!("failed to write {}: {}", path, e))?;
+        println!("saved pointer map to {}", path);
+    }
+
+    let target = match target {
+        Some(target) => target,
+        None => return Ok(()),
+    };
+    let defaults = ScanOptions::default();
+    let options = ScanOptions {
+        max_depth: args.number("depth")?.unwrap_or(defaults.max_depth),
+        max_offset: args.number("max-offset")?.unwrap_or(defaults.max_offset),
+        max_results: args.number("max-results")?.unwrap_or(defaults.max_results),
+        module: args.get("module").map(str::to_string),
+    };
+
+    let paths = map.scan(target, &options);
+    if paths.is_empty() {
+        return Err(format!("no static chains found for 0x{:X}", target));
+    }
+    for path in &paths {
+        println!("{}", path);
+    }
+
+    if let Some(path) = args.get("output") {
+        fs::write(path, pointer_scan::offsets_pack(target, &paths))
+            .map_err(|e| format!("failed to write {}: {}", path, e))?;
+        println!("wrote {} candidates to {}", paths.len(), path);
+    }
+    Ok(())
+}
+
+fn replay(args: &Args) -> Result<(), String> {
+    if args.positional.is_empty() {
+        return Err("replay requires at least one trace file".to_string());
+    }
+    let settings = detector_settings(args)?;
+    let tolerance = Duration::from_millis(args.number("tolerance")?.unwrap_or(1000) as u64);
+    println!("replaying with {}", settings);
+
+    let mut summary = ReplaySummary::default();
+    for path in &args.positional {
+        let trace = load_trace(path)?;
+        println!("{}:", path);
+        for (index, cast) in trace.casts().iter().enumerate() {
+            let outcome = cast.judge(cast.detect(&settings), tolerance);
+            let recorded = match cast.reel {
+                Some(_) => cast.judge(cast.reel, tolerance).to_string(),
+                None => "did not reel".to_string(),
+            };
+            println!(
+                "  cast {} at {:.1}s: {} (recorded: {})",
+                index + 1,
+                cast.start.as_secs_f32(),
+                outcome,
+                recorded
+            );
+            summary.add(outcome);
+        }
+    }
+    println!("{}", summary);
+
+    let failures = summary.casts - summary.hits - summary.quiet;
+    if args.get("strict").is_some() && failures > 0 {
+        return Err(format!("{} casts were not handled correctly", failures));
+    }
+    Ok(())
+}
+
+fn tune_detector(args: &Args) -> Result<(), String> {
+    if args.positional.is_empty() {
+        return Err("tune requires at least one trace file".to_string());
+    }
+    let kinds = match args.get("detector") {
+        Some(kind) => vec![kind.parse()?],
+        None => DetectorKind::ALL.to_vec(),
+    };
+    let tolerance = Duration::from_millis(args.number("tolerance")?.unwrap_or(1000) as u64);
+    let top = args.number("top")?.unwrap_or(10);
+
+    let mut casts: Vec<CastRecord> = Vec::new();
+    for path in &args.positional {
+        casts.extend(load_trace(path)?.casts());
+    }
+    let bites = casts.iter().filter(|cast| cast.bite.is_some()).count();
+    if bites == 0 {
+        return Err("the traces contain no labelled bites".to_string());
+    }
+    println!(
+        "tuning on {} casts with {} labelled bites",
+        casts.len(),
+        bites
+    );
+
+    let results = tune::tune(&casts, &kinds, tolerance);
+    let shown = if top == 0 { results.len() } else { top };
+    println!(
+        "{:>9} {:>6} {:>8}  settings",
+        "precision", "recall", "latency"
+    );
+    for result in results.iter().take(shown) {
+        let latency = result
+            .summary
+            .mean_latency()
+            .map(|latency| format!("{} ms", latency.as_millis()))
+            .unwrap_or_else(|| "-".to_string());
+        println!(
+            "{:>8.0}% {:>5.0}% {:>8}  {}",
+            result.summary.precision() * 100.0,
+            result.summary.recall() * 100.0,
+            latency,
+            result.settings
+        );
+    }
+
+    let best = results.first().ok_or("no configurations were tried")?;
+    println!("best: {}", best.settings);
+    println!("{}", best.summary);
+
+    if let Some(path) = args.get("output") {
+        best.settings.save(path)?;
+        println!("saved detector profile to {}", path);
+        return Ok(());
+    }
+
+    let path = fisher::config_path();
+    let mut config = if path.exists() {
+        FisherConfig::load(&path)?
+    } else {
+        FisherConfig::default()
+    };
+    config.detector = best.settings;
+    config.save(&path)?;
+    println!("saved the detector settings to {}", path.display());
+    Ok(())
+}
+
+fn load_trace(path: &str) -> Result<Trace, String> {
+    Trace::load(path).map_err(|e| format!("failed to read {}: {}", path, e))
+}
+
+fn detector_settings(args: &Args) -> Result<DetectorSettings, String> {
+    let mut settings = match args.get("profile") {
+        Some(path) => DetectorSettings::load(path)?,
+        None => DetectorSettings::default(),
+    };
+    if let Some(kind) = args.get("detector") {
+        settings.kind = kind.parse()?;
+    }
+
+    if let Some(threshold) = args.float("threshold")? {
+        settings.peak_fall.threshold = threshold as u32;
+        settings.change_point.threshold = threshold;
+    }
+    if let Some(stability) = args.number("stability")? {
+        settings.peak_fall.stability = stability as u32;
+    }
+    if let Some(min_peak) = args.number("min-peak")? {
+        settings.peak_fall.min_peak = min_peak as u32;
+        settings.edge.min_peak = min_peak as u32;
+        settings.change_point.min_peak = min_peak as u32;
+    }
+    if let Some(falling) = args.number("falling")? {
+        settings.peak_fall.falling = falling as u32;
+    }
+    if let Some(min_rate) = args.float("min-rate")? {
+        settings.edge.min_rate = min_rate;
+    }
+    if let Some(confirm) = args.number("confirm")? {
+        settings.edge.confirm = confirm as u32;
+    }
+    if let Some(window) = args.number("window")? {
+        settings.moving_average.window = window;
+    }
+    if let Some(rise) = args.float("rise")? {
+        settings.moving_average.rise = rise;
+    }
+    if let Some(fall) = args.float("fall")? {
+        settings.moving_average.fall = fall;
+    }
+    if let Some(drift) = args.float("drift")? {
+        settings.change_point.drift = drift;
+    }
+    Ok(settings)
+}
+
+fn list_processes(args: &Args) -> Result<(), String> {
+    let process = args.get("process").unwrap_or(memory::DEFAULT_PROCESS);
+    let processes = find_processes(process);
+    if processes.is_empty() {
+        return Err(format!("failed to find {} process", process));
+    }
+
+    for process in processes {
+        let started = process
+            .start_time
+            .and_then(|start| start.elapsed().ok())
+            .map(|age| format!("started {}m ago", age.as_secs() / 60))
+            .unwrap_or_default();
+        println!(
+            "{:>8} {:<18} {:<32} {}",
+            process.pid,
+            started,
+            process.window_title.unwrap_or_default(),
+            process
+                .path
+                .map(|path| path.display().to_string())
+                .unwrap_or_default()
+        );
+    }
+    Ok(())
+}
+
+enum Target {
+    Live(MemoryReader),
+    Snapshot(Snapshot),
+    Minidump(Minidump),
+}
+
+impl ProcessMemory for Target {
+    fn read_raw(&self, address: Address, buffer: &mut [u8]) -> memory::Result<()> {
+        match self {
+            Target::Live(memory) => memory.read_raw(address, buffer),
+            Target::Snapshot(memory) => memory.read_raw(address, buffer),
+            Target::Minidump(memory) => memory.read_raw(address, buffer),
+        }
+    }
+
+    fn modules(&self) -> memory::Result<Vec<ModuleInfo>> {
+        match self {
+            Target::Live(memory) => memory.modules(),
+            Target::Snapshot(memory) => memory.modules(),
+            Target::Minidump(memory) => memory.modules(),
+        }
+    }
+
+    fn regions(&self) -> memory::Result<Vec<MemoryRegion>> {
+        match self {
+            Target::Live(memory) => memory.regions(),
+            Target::Snapshot(memory) => memory.regions(),
+            Target::Minidump(memory) => memory.regions(),
+        }
+    }
+
+    fn region_at(&self, address: Address) -> memory::Result<Option<MemoryRegion>> {
+        match self {
+            Target::Live(memory) => memory.region_at(address),
+            Target::Snapshot(memory) => memory.region_at(address),
+            Target::Minidump(memory) => memory.region_at(address),
+        }
+    }
+
+    fn is_alive(&self) -> bool {
+        match self {
+            Target::Live(memory) => memory.is_alive(),
+            Target::Snapshot(_) | Target::Minidump(_) => true,
+        }
+    }
+}
+
+fn attach(args: &Args) -> Result<Target, String> {
+    if let Some(path) = args.get("snapshot") {
+        return Snapshot::load(path)
+            .map(Target::Snapshot)
+            .map_err(|e| format!("failed to read {}: {}", path, e));
+    }
+    if let Some(path) = args.get("minidump") {
+        return Minidump::open(path)
+            .map(Target::Minidump)
+            .map_err(|e| format!("failed to read {}: {}", path, e));
+    }
+
+    let pid = match args.get("pid") {
+        Some(pid) => pid.parse().map_err(|_| format!("invalid pid: {}", pid))?,
+        None => {
+            let process = args.get("process").unwrap_or(memory::DEFAULT_PROCESS);
+            get_pid(process).ok_or_else(|| format!("failed to find {} process", process))?
+        }
+    };
+    MemoryReader::new(pid)
+        .map(Target::Live)
+        .map_err(|e| format!("failed to attach to {}: {}", pid, e.explain()))
+}
+
+fn parse_address(value: &str) -> Result<Address, String> {
+    let digits = value.trim_start_matches("0x").trim_start_matches("0X");
+    Address::from_str_radix(digits, 16).map_err(|_| format!("invalid address: {}", value))
+}
+
+fn parse_number(value: &str) -> Result<usize, String> {
+    match value.strip_prefix("0x") {
+        Some(hex) => usize::from_str_radix(hex, 16),
+        None => value.parse(),
+    }
+    .map_err(|_| format!("invalid number: {}", value))
+}
+
+#[cfg(windows)]
+fn attach_console() {
+    use winapi::um::wincon::{AttachConsole, ATTACH_PARENT_PROCESS};
+    unsafe { AttachConsole(ATTACH_PARENT_PROCESS) };
+}
+
+#[cfg(not(windows))]
+fn attach_console() {}
@@ -0,0 +1,514 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+const DEFAULT_MIN_PEAK: u32 = 5;
+const WARMUP_SAMPLES: u32 = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Sample {
+    pub time: Duration,
+    pub value: u32,
+}
+
+pub trait BiteDetector: Send {
+    fn update(&mut self, sample: Sample) -> bool;
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DetectorKind {
+    #[default]
+    PeakFall,
+    Edge,
+    MovingAverage,
+    ChangePoint,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PeakFallConfig {
+    pub threshold: u32,
+    pub stability: u32,
+    pub min_peak: u32,
+    pub falling: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EdgeConfig {
+    pub min_rate: f32,
+    pub confirm: u32,
+    pub min_peak: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MovingAverageConfig {
+    pub window: usize,
+    pub rise: f32,
+    pub fall: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChangePointConfig {
+    pub drift: f32,
+    pub threshold: f32,
+    pub min_peak: u32,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DetectorSettings {
+    pub kind: DetectorKind,
+    pub peak_fall: PeakFallConfig,
+    pub edge: EdgeConfig,
+    pub moving_average: MovingAverageConfig,
+    pub change_point: ChangePointConfig,
+}
+
+impl DetectorKind {
+    pub const ALL: [DetectorKind; 4] = [
+        DetectorKind::PeakFall,
+        DetectorKind::Edge,
+        DetectorKind::MovingAverage,
+        DetectorKind::ChangePoint,
+    ];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            DetectorKind::PeakFall => "peak-fall",
+            DetectorKind::Edge => "edge",
+            DetectorKind::MovingAverage => "moving-average",
+            DetectorKind::ChangePoint => "change-point",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DetectorKind::PeakFall => "Peak and fall",
+            DetectorKind::Edge => "Falling edge",
+            DetectorKind::MovingAverage => "Moving average",
+            DetectorKind::ChangePoint => "Change point",
+        }
+    }
+}
+
+impl fmt::Display for DetectorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl fmt::Display for DetectorSettings {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.kind.id())?;
+        match self.kind {
+            DetectorKind::PeakFall => {
+                let config = &self.peak_fall;
+                write!(
+                    f,
+                    " threshold={} stability={} min-peak={} falling={}",
+                    config.threshold, config.stability, config.min_peak, config.falling
+                )
+            }
+            DetectorKind::Edge => write!(
+                f,
+                " min-rate={} confirm={} min-peak={}",
+                self.edge.min_rate, self.edge.confirm, self.edge.min_peak
+            ),
+            DetectorKind::MovingAverage => {
+                let config = &self.moving_average;
+                write!(
+                    f,
+                    " window={} rise={} fall={}",
+                    config.window, config.rise, config.fall
+                )
+            }
+            DetectorKind::ChangePoint => {
+                let config = &self.change_point;
+                write!(
+                    f,
+                    " drift={} threshold={} min-peak={}",
+                    config.drift, config.threshold, config.min_peak
+                )
+            }
+        }
+    }
+}
+
+impl FromStr for DetectorKind {
+    type Err = String;
+
+    fn from_str(id: &str) -> Result<Self, Self::Err> {
+        DetectorKind::ALL
+            .into_iter()
+            .find(|kind| kind.id() == id)
+            .ok_or_else(|| format!("unknown detector: {}", id))
+    }
+}
+
+impl Default for PeakFallConfig {
+    fn default() -> Self {
+        PeakFallConfig {
+            threshold: 1,
+            stability: 3,
+            min_peak: DEFAULT_MIN_PEAK,
+            falling: 2,
+        }
+    }
+}
+
+impl Default for EdgeConfig {
+    fn default() -> Self {
+        EdgeConfig {
+            min_rate: 10.0,
+            confirm: 2,
+            min_peak: DEFAULT_MIN_PEAK,
+        }
+    }
+}
+
+impl Default for MovingAverageConfig {
+    fn default() -> Self {
+        MovingAverageConfig {
+            window: 4,
+            rise: 5.0,
+            fall: 2.0,
+        }
+    }
+}
+
+impl Default for ChangePointConfig {
+    fn default() -> Self {
+        ChangePointConfig {
+            drift: 0.5,
+            threshold: 4.0,
+            min_peak: DEFAULT_MIN_PEAK,
+        }
+    }
+}
+
+impl DetectorSettings {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path)
+            .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+        let settings: DetectorSettings = toml::from_str(&source)
+            .map_err(|e| format!("invalid detector profile {}: {}", path.display(), e))?;
+        settings
+            .validate()
+            .map_err(|e| format!("invalid detector profile {}: {}", path.display(), e))?;
+        Ok(settings)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        let path = path.as_ref();
+        let source = toml::to_string(self).map_err(|e| e.to_string())?;
+        fs::write(path, source).map_err(|e| format!("failed to write {}: {}", path.display(), e))
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let values = [
+            ("edge.min_rate", self.edge.min_rate),
+            ("moving_average.rise", self.moving_average.rise),
+            ("moving_average.fall", self.moving_average.fall),
+            ("change_point.drift", self.change_point.drift),
+            ("change_point.threshold", self.change_point.threshold),
+        ];
+        for (name, value) in values {
+            if !value.is_finite() || value < 0.0 {
+                return Err(format!(
+                    "detector.{} must be a non-negative number, got {}",
+                    name, value
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn build(&self) -> Box<dyn BiteDetector> {
+        match self.kind {
+            DetectorKind::PeakFall => Box::new(PeakFall::new(self.peak_fall)),
+            DetectorKind::Edge => Box::new(Edge::new(self.edge)),
+            DetectorKind::MovingAverage => Box::new(MovingAverage::new(self.moving_average)),
+            DetectorKind::ChangePoint => Box::new(ChangePoint::new(self.change_point)),
+        }
+    }
+}
+
+pub struct PeakFall {
+    config: PeakFallConfig,
+    peak_value: u32,
+    last_value: u32,
+    falling_count: u32,
+    stable_count: u32,
+    post_peak_count: u32,
+}
+
+impl PeakFall {
+    pub fn new(config: PeakFallConfig) -> Self {
+        PeakFall {
+            config,
+            peak_value: 0,
+            last_value: 0,
+            falling_count: 0,
+            stable_count: 0,
+            post_peak_count: 0,
+        }
+    }
+}
+
+impl BiteDetector for PeakFall {
+    fn update(&mut self, sample: Sample) -> bool {
+        let current_value = sample.value;
+        if current_value > self.peak_value {
+            self.peak_value = current_value;
+            self.falling_count = 0;
+            self.stable_count = 0;
+            self.post_peak_count = 0;
+        } else if current_value < self.last_value {
+            self.falling_count += 1;
+            self.stable_count = 0;
+            if self.falling_count > self.config.falling && self.peak_value >= self.config.min_peak {
+                self.post_peak_count += 1;
+                if self.post_peak_count > self.config.threshold {
+                    return true;
+                }
+            }
+        } else if current_value == self.last_value {
+            self.stable_count += 1;
+            if self.stable_count >= self.config.stability && self.peak_value >= self.config.min_peak
+            {
+                self.post_peak_count += 1;
+                if self.post_peak_count > self.config.threshold {
+                    return true;
+                }
+            }
+        } else {
+            self.falling_count = 0;
+            self.stable_count = 0;
+        }
+        self.last_value = current_value;
+        false
+    }
+}
+
+pub struct Edge {
+    config: EdgeConfig,
+    peak_value: u32,
+    last: Option<Sample>,
+    falling_count: u32,
+}
+
+impl Edge {
+    pub fn new(config: EdgeConfig) -> Self {
+        Edge {
+            config,
+            peak_value: 0,
+            last: None,
+            falling_count: 0,
+        }
+    }
+}
+
+impl BiteDetector for Edge {
+    fn update(&mut self, sample: Sample) -> bool {
+        self.peak_value = self.peak_value.max(sample.value);
+        let last = match self.last.replace(sample) {
+            Some(last) => last,
+            None => return false,
+        };
+
+        let elapsed = sample.time.saturating_sub(last.time).as_secs_f32();
+        if elapsed <= 0.0 {
+            return false;
+        }
+        let rate = (sample.value as f32 - last.value as f32) / elapsed;
+        if rate <= -self.config.min_rate && self.peak_value >= self.config.min_peak {
+            self.falling_count += 1;
+        } else {
+            self.falling_count = 0;
+        }
+        self.falling_count >= self.config.confirm.max(1)
+    }
+}
+
+pub struct MovingAverage {
+    config: MovingAverageConfig,
+    values: VecDeque<u32>,
+    baseline: Option<f32>,
+    peak: f32,
+    armed: bool,
+}
+
+impl MovingAverage {
+    pub fn new(config: MovingAverageConfig) -> Self {
+        MovingAverage {
+            config,
+            values: VecDeque::new(),
+            baseline: None,
+            peak: 0.0,
+            armed: false,
+        }
+    }
+}
+
+impl BiteDetector for MovingAverage {
+    fn update(&mut self, sample: Sample) -> bool {
+        self.values.push_back(sample.value);
+        while self.values.len() > self.config.window.max(1) {
+            self.values.pop_front();
+        }
+        let average =
+            self.values.iter().map(|&value| value as f32).sum::<f32>() / self.values.len() as f32;
+
+        let baseline = *self.baseline.get_or_insert(average);
+        self.peak = self.peak.max(average);
+        if !self.armed {
+            self.armed = self.peak >= baseline + self.config.rise;
+            return false;
+        }
+        average <= self.peak - self.config.fall
+    }
+}
+
+pub struct ChangePoint {
+    config: ChangePointConfig,
+    last_value: Option<u32>,
+    count: u32,
+    mean: f32,
+    variance: f32,
+    sum: f32,
+    peak_value: u32,
+}
+
+impl ChangePoint {
+    pub fn new(config: ChangePointConfig) -> Self {
+        ChangePoint {
+            config,
+            last_value: None,
+            count: 0,
+            mean: 0.0,
+            variance: 0.0,
+            sum: 0.0,
+            peak_value: 0,
+        }
+    }
+}
+
+impl BiteDetector for ChangePoint {
+    fn update(&mut self, sample: Sample) -> bool {
+        self.peak_value = self.peak_value.max(sample.value);
+        let last_value = match self.last_value.replace(sample.value) {
+            Some(last_value) => last_value,
+            None => return false,
+        };
+
+        let step = sample.value as f32 - last_value as f32;
+        if self.count >= WARMUP_SAMPLES && self.peak_value >= self.config.min_peak {
+            let deviation = self.variance.sqrt().max(1.0);
+            self.sum = (self.sum + (self.mean - step) / deviation - self.config.drift).max(0.0);
+            if self.sum > self.config.threshold {
+                return true;
+            }
+        }
+
+        self.count += 1;
+        let delta = step - self.mean;
+        self.mean += delta / self.count as f32;
+        self.variance += (delta * (step - self.mean) - self.variance) / self.count as f32;
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fires_at(detector: &mut dyn BiteDetector, values: &[u32]) -> Option<usize> {
+        values.iter().enumerate().position(|(index, &value)| {
+            detector.update(Sample {
+                time: Duration::from_millis(50 * index as u64),
+                value,
+            })
+        })
+    }
+
+    fn fires_with(kind: DetectorKind, values: &[u32]) -> Option<usize> {
+        let settings = DetectorSettings {
+            kind,
+            ..Default::default()
+        };
+        fires_at(settings.build().as_mut(), values)
+    }
+
+    #[test]
+    fn peak_fall_matches_the_baseline_heuristic() {
+        let kind = DetectorKind::PeakFall;
+        assert_eq!(fires_with(kind, &[1, 3, 6, 9, 12, 10, 8, 6, 4]), Some(8));
+        assert_eq!(fires_with(kind, &[1, 3, 6, 6, 6, 6, 6]), Some(6));
+        assert_eq!(fires_with(kind, &[0; 12]), None);
+        assert_eq!(fires_with(kind, &[1, 3, 2, 4, 3, 4, 2, 3, 2, 1]), None);
+
+        let mut eager = PeakFall::new(PeakFallConfig {
+            threshold: 0,
+            ..Default::default()
+        });
+        assert_eq!(
+            fires_at(&mut eager, &[1, 3, 6, 9, 12, 10, 8, 6, 4]),
+            Some(7)
+        );
+    }
+
+    #[test]
+    fn edge_fires_on_a_steady_fall() {
+        let kind = DetectorKind::Edge;
+        assert_eq!(fires_with(kind, &[0, 2, 5, 8, 8, 7, 6]), Some(6));
+        assert_eq!(fires_with(kind, &[8; 10]), None);
+        assert_eq!(fires_with(kind, &[8, 7, 8, 7, 8, 7, 8, 7]), None);
+        assert_eq!(fires_with(kind, &[1, 2, 3, 2, 1, 0]), None);
+    }
+
+    #[test]
+    fn moving_average_fires_after_rising_and_falling() {
+        let kind = DetectorKind::MovingAverage;
+        assert_eq!(
+            fires_with(kind, &[0, 0, 0, 0, 8, 8, 8, 8, 8, 4, 0, 0]),
+            Some(10)
+        );
+        assert_eq!(fires_with(kind, &[3; 10]), None);
+        assert_eq!(fires_with(kind, &[3, 5, 3, 5, 3, 5, 3, 5]), None);
+    }
+
+    #[test]
+    fn change_point_fires_on_a_step_down() {
+        let kind = DetectorKind::ChangePoint;
+        assert_eq!(
+            fires_with(kind, &[0, 2, 4, 6, 8, 10, 10, 10, 10, 7, 4, 1]),
+            Some(9)
+        );
+        assert_eq!(fires_with(kind, &[6; 12]), None);
+        assert_eq!(fires_with(kind, &[6, 7, 6, 7, 6, 7, 6, 7, 6, 7]), None);
+        assert_eq!(fires_with(kind, &[0, 1, 2, 3, 4, 3, 2, 1, 0]), None);
+    }
+
+    #[test]
+    fn rejects_invalid_profiles() {
+        let path =
+            std::env::temp_dir().join(format!("mcbe-fisher-profile-{}.toml", std::process::id()));
+        fs::write(&path, "kind = \"edge\"\n\n[edge]\nmin_rate = -1.0\n").unwrap();
+        let invalid = DetectorSettings::load(&path);
+        fs::write(&path, "kind = \"edge\"\n\n[edge]\nmin_rate = 4.0\n").unwrap();
+        let valid = DetectorSettings::load(&path);
+        let _ = fs::remove_file(&path);
+
+        assert!(invalid.unwrap_err().contains("edge.min_rate"));
+        assert_eq!(valid.unwrap().edge.min_rate, 4.0);
+    }
+}
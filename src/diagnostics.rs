@@ -0,0 +1,456 @@
+use eframe::egui;
+use egui::{Color32, RichText};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::memory::{MemoryReader, ModuleInfo, PointerTrace, ProcessMemory, Snapshot};
+use crate::offsets::{self, OffsetDatabase};
+use crate::value_scan::{Filter, ValueScanner, ValueType};
+
+const ERROR_COLOR: Color32 = Color32::from_rgb(248, 81, 73);
+const SNAPSHOT_RADIUS: usize = 0x1000;
+const MAX_LISTED_CANDIDATES: usize = 100;
+const SCAN_GUIDE: [&str; 4] = [
+    "1. hold the rod without casting and press New scan",
+    "2. cast the rod, wait for the bobber to land and press Changed",
+    "3. wait a moment and press Unchanged",
+    "4. reel in, press Changed and repeat until a few candidates are left",
+];
+
+enum ScanRequest {
+    First(Option<String>),
+    Next(Filter),
+}
+
+pub struct Diagnostics {
+    pub open: bool,
+    modules: Vec<ModuleInfo>,
+    error: Option<String>,
+    trace: Option<Arc<PointerTrace>>,
+    trace_error: Option<String>,
+    fisher_trace: Option<Arc<PointerTrace>>,
+    snapshot_path: Option<PathBuf>,
+    scan_type: ValueType,
+    scan_value: String,
+    scanner: Option<ValueScanner>,
+    scan_thread: Option<thread::JoinHandle<(ValueScanner, Option<String>)>>,
+    scan_error: Option<String>,
+}
+
+impl Diagnostics {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            modules: Vec::new(),
+            error: None,
+            trace: None,
+            trace_error: None,
+            fisher_trace: None,
+            snapshot_path: None,
+            scan_type: ValueType::U32,
+            scan_value: String::new(),
+            scanner: Some(ValueScanner::new(ValueType::U32)),
+            scan_thread: None,
+            scan_error: None,
+        }
+    }
+
+    pub fn set_trace(&mut self, trace: Arc<PointerTrace>) {
+        let pushed = self
+            .fisher_trace
+            .as_ref()
+            .is_some_and(|last| Arc::ptr_eq(last, &trace));
+        if pushed {
+            return;
+        }
+        self.fisher_trace = Some(trace.clone());
+        self.trace = Some(trace);
+        self.trace_error = None;
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, pid: Option<u32>) {
+        if !self.open {
+            return;
+        }
+
+        ctx.show_viewport_immediate(
+            egui::ViewportId::from_hash_of("diagnostics"),
+            egui::ViewportBuilder::default()
+                .with_title("mcbe-fisher-rs diagnostics")
+                .with_inner_size([560.0, 560.0]),
+            |ctx, _class| {
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    egui::ScrollArea::vertical().show(ui, |ui| {
+                        self.show_trace(ui, pid);
+                        ui.separator();
+                        self.show_value_scan(ui, pid);
+                        ui.separator();
+                        self.show_modules(ui, pid);
+                    });
+                });
+
+                if self.scan_thread.is_some() {
+                    self.poll_scan();
+                    ctx.request_repaint();
+                }
+
+                if ctx.input(|i| i.viewport().close_requested()) {
+                    self.open = false;
+                }
+            },
+        );
+    }
+
+    fn show_trace(&mut self, ui: &mut egui::Ui, pid: Option<u32>) {
+        egui::CollapsingHeader::new(RichText::new("Pointer chain").size(16.0))
+            .default_open(true)
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button("Trace now").clicked() {
+                        self.trace_now(pid);
+                    }
+                    if ui.button("Save snapshot").clicked() {
+                        self.save_snapshot(pid);
+                    }
+                });
+
+                if let Some(path) = &self.snapshot_path {
+                    ui.label(format!("saved snapshot to {}", path.display()));
+                }
+
+                if let Some(error) = &self.trace_error {
+                    ui.label(RichText::new(error).color(ERROR_COLOR));
+                }
+
+                let trace = match &self.trace {
+                    Some(trace) => trace,
+                    None => return,
+                };
+
+                egui::Grid::new("trace_grid")
+                    .striped(true)
+                    .num_columns(5)
+                    .show(ui, |ui| {
+                        ui.label("hop");
+                        ui.label("address");
+                        ui.label("value");
+                        ui.label("offset");
+                        ui.label("next");
+                        ui.end_row();
+
+                        for (index, hop) in trace.hops.iter().enumerate() {
+                            ui.label(index.to_string());
+                            ui.monospace(format!("0x{:X}", hop.address));
+                            match (hop.value, hop.next()) {
+                                (Some(value), Some(next)) => {
+                                    ui.monospace(format!("0x{:X}", value));
+                                    ui.monospace(format!("0x{:X}", hop.offset));
+                                    ui.monospace(format!("0x{:X}", next));
+                                }
+                                _ => {
+                                    ui.label(RichText::new("failed").color(ERROR_COLOR));
+                                    ui.monospace(format!("0x{:X}", hop.offset));
+                                    ui.label("");
+                                }
+                            }
+                            ui.end_row();
+                        }
+                    });
+
+                match (trace.address(), trace.failure()) {
+                    (Some(address), _) => {
+                        ui.label(format!("rod state at 0x{:X}", address));
+                    }
+                    (None, Some(failure)) => {
+                        ui.label(RichText::new(failure).color(ERROR_COLOR));
+                    }
+                    _ => {}
+                }
+            });
+    }
+
+    fn trace_now(&mut self, pid: Option<u32>) {
+        self.trace_error = None;
+
+        let result = pid
+            .ok_or_else(|| "failed to find game process".to_string())
+            .and_then(|pid| MemoryReader::new(pid).map_err(|e| e.explain()))
+            .and_then(|memory| {
+                let database = OffsetDatabase::load()?;
+                let (entry, module) = database.resolve(&memory)?;
+                let base = entry.static_base(&memory, &module);
+                Ok(memory.trace_pointers(base, &entry.offsets))
+            });
+
+        match result {
+            Ok(trace) => self.trace = Some(Arc::new(trace)),
+            Err(e) => self.trace_error = Some(e),
+        }
+    }
+
+    fn save_snapshot(&mut self, pid: Option<u32>) {
+        self.trace_error = None;
+        self.snapshot_path = None;
+
+        let path = offsets::exe_dir().join(format!(
+            "snapshot-{}.mcsnap",
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs()
+        ));
+        let result = pid
+            .ok_or_else(|| "failed to find game process".to_string())
+            .and_then(|pid| MemoryReader::new(pid).map_err(|e| e.explain()))
+            .and_then(|memory| {
+                let database = OffsetDatabase::load()?;
+                let (entry, module) = database.resolve(&memory)?;
+                let base = entry.static_base(&memory, &module);
+                let trace = memory.trace_pointers(base, &entry.offsets);
+                let snapshot = Snapshot::capture(&memory, &trace.addresses(), SNAPSHOT_RADIUS)
+                    .map_err(|e| format!("failed to capture snapshot: {}", e.explain()))?;
+                snapshot
+                    .save(&path)
+                    .map_err(|e| format!("failed to write {}: {}", path.display(), e))?;
+                Ok(trace)
+            });
+
+        match result {
+            Ok(trace) => {
+                self.trace = Some(Arc::new(trace));
+                self.snapshot_path = Some(path);
+            }
+            Err(e) => self.trace_error = Some(e),
+        }
+    }
+
+    fn show_value_scan(&mut self, ui: &mut egui::Ui, pid: Option<u32>) {
+        egui::CollapsingHeader::new(RichText::new("Value scan").size(16.0))
+            .default_open(false)
+            .show(ui, |ui| {
+                for step in SCAN_GUIDE {
+                    ui.label(step);
+                }
+                ui.add_space(4.0);
+
+                let scanner = match &self.scanner {
+                    Some(scanner) => scanner,
+                    None => {
+                        ui.horizontal(|ui| {
+                            ui.spinner();
+                            ui.label("scanning");
+                        });
+                        return;
+                    }
+                };
+                let started = scanner.is_started();
+
+                let mut request = None;
+                let mut reset = false;
+                ui.horizontal(|ui| {
+                    ui.add_enabled_ui(!started, |ui| {
+                        egui::ComboBox::from_id_source("scan_type")
+                            .width(60.0)
+                            .selected_text(self.scan_type.name())
+                            .show_ui(ui, |ui| {
+                                for value_type in ValueType::ALL {
+                                    ui.selectable_value(
+                                        &mut self.scan_type,
+                                        value_type,
+                                        value_type.name(),
+                                    );
+                                }
+                            });
+                    })
+                    .response
+                    .on_disabled_hover_text("reset the scan to change the value type");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.scan_value)
+                            .hint_text("value (optional)")
+                            .desired_width(120.0),
+                    );
+                    if ui.button("New scan").clicked() {
+                        let value = self.scan_value.trim();
+                        request = Some(ScanRequest::First(
+                            (!value.is_empty()).then(|| value.to_string()),
+                        ));
+                    }
+                    if ui
+                        .add_enabled(started, egui::Button::new("Reset"))
+                        .clicked()
+                    {
+                        reset = true;
+                    }
+                });
+
+                ui.add_enabled_ui(started, |ui| {
+                    ui.horizontal(|ui| {
+                        if ui.button("Exact").clicked() {
+                            match self.scan_type.parse_value(&self.scan_value) {
+                                Ok(value) => {
+                                    request = Some(ScanRequest::Next(Filter::Exact(value)))
+                                }
+                                Err(e) => self.scan_error = Some(e),
+                            }
+                        }
+                        let filters = [
+                            ("Changed", Filter::Changed),
+                            ("Unchanged", Filter::Unchanged),
+                            ("Increased", Filter::Increased),
+                            ("Decreased", Filter::Decreased),
+                        ];
+                        for (label, filter) in filters {
+                            if ui.button(label).clicked() {
+                                request = Some(ScanRequest::Next(filter));
+                            }
+                        }
+                    });
+                });
+
+                ui.label(scanner.summary());
+                if let Some(error) = &self.scan_error {
+                    ui.label(RichText::new(error).color(ERROR_COLOR));
+                }
+
+                let candidates = scanner.candidates();
+                if !candidates.is_empty() && candidates.len() <= MAX_LISTED_CANDIDATES {
+                    egui::Grid::new("candidates_grid")
+                        .striped(true)
+                        .num_columns(3)
+                        .show(ui, |ui| {
+                            for candidate in candidates {
+                                ui.monospace(format!("0x{:X}", candidate.address));
+                                ui.monospace(candidate.value.to_string());
+                                if ui.small_button("Copy").clicked() {
+                                    ui.output_mut(|o| {
+                                        o.copied_text = format!("0x{:X}", candidate.address)
+                                    });
+                                }
+                                ui.end_row();
+                            }
+                        });
+                }
+
+                if reset {
+                    self.scanner = Some(ValueScanner::new(self.scan_type));
+                    self.scan_error = None;
+                } else if let Some(request) = request {
+                    self.start_scan(request, pid);
+                }
+            });
+    }
+
+    fn start_scan(&mut self, request: ScanRequest, pid: Option<u32>) {
+        self.scan_error = None;
+
+        let pid = match pid {
+            Some(pid) => pid,
+            None => {
+                self.scan_error = Some("failed to find game process".to_string());
+                return;
+            }
+        };
+
+        let scan_type = self.scan_type;
+        let mut scanner = match self.scanner.take() {
+            Some(scanner) if scanner.value_type == scan_type => scanner,
+            _ => ValueScanner::new(scan_type),
+        };
+        if let ScanRequest::First(_) = request {
+            scanner = ValueScanner::new(scan_type);
+        }
+
+        self.scan_thread = Some(thread::spawn(move || {
+            let memory = match MemoryReader::new(pid) {
+                Ok(memory) => memory,
+                Err(e) => return (scanner, Some(e.explain())),
+            };
+
+            let result = match request {
+                ScanRequest::First(value) => {
+                    match value.map(|value| scan_type.parse_value(&value)).transpose() {
+                        Ok(exact) => scanner.first_scan(&memory, exact).map_err(|e| e.explain()),
+                        Err(e) => Err(e),
+                    }
+                }
+                ScanRequest::Next(filter) => {
+                    scanner.next_scan(&memory, filter).map_err(|e| e.explain())
+                }
+            };
+            (scanner, result.err())
+        }));
+    }
+
+    fn poll_scan(&mut self) {
+        if !self
+            .scan_thread
+            .as_ref()
+            .is_some_and(|thread| thread.is_finished())
+        {
+            return;
+        }
+
+        match self.scan_thread.take().unwrap().join() {
+            Ok((scanner, error)) => {
+                self.scanner = Some(scanner);
+                self.scan_error = error.map(|e| format!("failed to scan: {}", e));
+            }
+            Err(_) => {
+                self.scanner = Some(ValueScanner::new(self.scan_type));
+                self.scan_error = Some("scan thread panicked".to_string());
+            }
+        }
+    }
+
+    fn show_modules(&mut self, ui: &mut egui::Ui, pid: Option<u32>) {
+        egui::CollapsingHeader::new(RichText::new("Modules").size(16.0))
+            .default_open(true)
+            .show(ui, |ui| {
+                if ui.button("Refresh").clicked() {
+                    self.refresh_modules(pid);
+                }
+
+                if let Some(error) = &self.error {
+                    ui.label(RichText::new(error).color(ERROR_COLOR));
+                }
+
+                egui::ScrollArea::vertical()
+                    .id_source("modules")
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("modules_grid")
+                            .striped(true)
+                            .num_columns(3)
+                            .show(ui, |ui| {
+                                for module in &self.modules {
+                                    ui.label(&module.name)
+                                        .on_hover_text(module.path.display().to_string());
+                                    ui.monospace(format!("0x{:X}", module.base));
+                                    ui.monospace(format!("0x{:X}", module.size));
+                                    ui.end_row();
+                                }
+                            });
+                    });
+            });
+    }
+
+    fn refresh_modules(&mut self, pid: Option<u32>) {
+        self.modules.clear();
+        self.error = None;
+
+        let pid = match pid {
+            Some(pid) => pid,
+            None => {
+                self.error = Some("failed to find game process".to_string());
+                return;
+            }
+        };
+
+        match MemoryReader::new(pid).and_then(|memory| memory.modules()) {
+            Ok(modules) => self.modules = modules,
+            Err(e) => self.error = Some(format!("failed to enumerate modules: {}", e.explain())),
+        }
+    }
+}
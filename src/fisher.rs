@@ -1,22 +1,68 @@
+use crate::detector::{BiteDetector, DetectorSettings, Sample};
+use crate::hook::{DipDetector, HookReader};
 use crate::input::{self, Key};
-use crate::memory::{Address, MemoryReader};
+use crate::memory::{Address, MemoryError, PageCache, PointerTrace, ProcessMemory};
+use crate::offsets::{self, OffsetDatabase};
+use crate::trace::{TraceEvent, TraceRecorder};
 use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::mpsc::Sender;
 use std::sync::Arc;
 use std::thread;
 use std::time::{Duration, Instant};
 
-const BASE_ADDRESS: usize = 0x05A5D218;
-const OFFSETS: &[usize] = &[0, 0x230, 0x18, 0x798, 0x48, 0x10, 0x78, 0xC];
-const CAST_DELAY: Duration = Duration::from_secs(1);
-const INITIAL_DELAY: Duration = Duration::from_secs(5);
-const POLL_INTERVAL: Duration = Duration::from_millis(50);
+pub const INITIAL_DELAY_RANGE: RangeInclusive<u64> = 0..=60_000;
+pub const CAST_DELAY_RANGE: RangeInclusive<u64> = 0..=10_000;
+pub const POLL_INTERVAL_RANGE: RangeInclusive<u64> = 10..=1_000;
+pub const BITE_TIMEOUT_RANGE: RangeInclusive<u64> = 5_000..=600_000;
+pub const RECAST_DELAY_RANGE: RangeInclusive<u64> = 0..=10_000;
+pub const KEY_HOLD_RANGE: RangeInclusive<u64> = 10..=1_000;
+pub const HOOK_REEL_DELAY_RANGE: RangeInclusive<u64> = 0..=5_000;
+const MAX_READ_FAILURES: u32 = 20;
+const MAX_ROD_STATE: u32 = 10_000;
+const STUCK_TIMEOUT: Duration = Duration::from_secs(15);
+const HOOK_RESCAN_FAILURES: u32 = 5;
+const HOOK_RESCAN_INTERVAL: Duration = Duration::from_secs(2);
+const RESOLVE_RETRY_INTERVAL: Duration = Duration::from_secs(2);
 
-pub struct Fisher {
-    memory: Arc<MemoryReader>,
+pub struct Fisher<M: ProcessMemory> {
+    cache: PageCache<M>,
     rod_address: Mutex<Option<Address>>,
+    rod_chain: Mutex<Option<(Address, Vec<usize>)>>,
     should_stop: AtomicBool,
+    process_exited: AtomicBool,
+    offsets: OffsetDatabase,
+    last_error: Mutex<Option<String>>,
+    last_trace: Mutex<Option<Arc<PointerTrace>>>,
+    hook_reader: Mutex<Option<HookReader>>,
+    last_hook: Mutex<Option<Address>>,
+    trace: Option<Arc<TraceRecorder>>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DetectionMode {
+    #[default]
+    RodState,
+    HookDip,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FisherConfig {
+    pub max_casts: Option<i32>,
+    pub mode: DetectionMode,
+    pub detector: DetectorSettings,
+    pub initial_delay_ms: u64,
+    pub cast_delay_ms: u64,
+    pub poll_interval_ms: u64,
+    pub bite_timeout_ms: u64,
+    pub recast_delay_ms: u64,
+    pub key_hold_ms: u64,
+    pub hook_reel_delay_ms: u64,
 }
 
 #[derive(PartialEq)]
@@ -26,32 +72,175 @@ enum FishingState {
     Reeling,
 }
 
-impl Fisher {
-    pub fn new(memory: Arc<MemoryReader>) -> Self {
+pub fn config_path() -> PathBuf {
+    offsets::exe_dir().join("fisher.toml")
+}
+
+impl Default for FisherConfig {
+    fn default() -> Self {
+        FisherConfig {
+            max_casts: None,
+            mode: DetectionMode::RodState,
+            detector: DetectorSettings::default(),
+            initial_delay_ms: 5_000,
+            cast_delay_ms: 1_000,
+            poll_interval_ms: 50,
+            bite_timeout_ms: 60_000,
+            recast_delay_ms: 1_000,
+            key_hold_ms: 50,
+            hook_reel_delay_ms: 50,
+        }
+    }
+}
+
+impl FisherConfig {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path)
+            .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+        let config: FisherConfig =
+            toml::from_str(&source).map_err(|e| format!("invalid {}: {}", path.display(), e))?;
+        config
+            .validate()
+            .map_err(|e| format!("invalid {}: {}", path.display(), e))?;
+        Ok(config)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        let path = path.as_ref();
+        let source = toml::to_string(self).map_err(|e| e.to_string())?;
+        fs::write(path, source).map_err(|e| format!("failed to write {}: {}", path.display(), e))
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(max) = self.max_casts {
+            if max < 1 {
+                return Err(format!("max_casts must be at least 1, got {}", max));
+            }
+        }
+
+        let timings = [
+            (
+                "initial_delay_ms",
+                self.initial_delay_ms,
+                INITIAL_DELAY_RANGE,
+            ),
+            ("cast_delay_ms", self.cast_delay_ms, CAST_DELAY_RANGE),
+            (
+                "poll_interval_ms",
+                self.poll_interval_ms,
+                POLL_INTERVAL_RANGE,
+            ),
+            ("bite_timeout_ms", self.bite_timeout_ms, BITE_TIMEOUT_RANGE),
+            ("recast_delay_ms", self.recast_delay_ms, RECAST_DELAY_RANGE),
+            ("key_hold_ms", self.key_hold_ms, KEY_HOLD_RANGE),
+            (
+                "hook_reel_delay_ms",
+                self.hook_reel_delay_ms,
+                HOOK_REEL_DELAY_RANGE,
+            ),
+        ];
+        for (name, value, range) in timings {
+            if !range.contains(&value) {
+                return Err(format!(
+                    "{} must be between {} and {}, got {}",
+                    name,
+                    range.start(),
+                    range.end(),
+                    value
+                ));
+            }
+        }
+
+        if self.bite_timeout_ms <= self.cast_delay_ms {
+            return Err("bite_timeout_ms must be longer than cast_delay_ms".to_string());
+        }
+        self.detector.validate()
+    }
+
+    pub fn initial_delay(&self) -> Duration {
+        Duration::from_millis(self.initial_delay_ms)
+    }
+
+    fn cast_delay(&self) -> Duration {
+        Duration::from_millis(self.cast_delay_ms)
+    }
+
+    fn hook_reel_delay(&self) -> Duration {
+        Duration::from_millis(self.hook_reel_delay_ms)
+    }
+
+    fn poll_interval(&self) -> Duration {
+        Duration::from_millis(self.poll_interval_ms)
+    }
+
+    fn bite_timeout(&self) -> Duration {
+        Duration::from_millis(self.bite_timeout_ms)
+    }
+
+    fn recast_delay(&self) -> Duration {
+        Duration::from_millis(self.recast_delay_ms)
+    }
+
+    fn key_hold(&self) -> Duration {
+        Duration::from_millis(self.key_hold_ms)
+    }
+}
+
+impl<M: ProcessMemory> Fisher<M> {
+    pub fn new(memory: Arc<M>, offsets: OffsetDatabase, trace: Option<Arc<TraceRecorder>>) -> Self {
         Fisher {
-            memory,
+            cache: PageCache::new(memory),
             rod_address: Mutex::new(None),
+            rod_chain: Mutex::new(None),
             should_stop: AtomicBool::new(false),
+            process_exited: AtomicBool::new(false),
+            offsets,
+            last_error: Mutex::new(None),
+            last_trace: Mutex::new(None),
+            hook_reader: Mutex::new(None),
+            last_hook: Mutex::new(None),
+            trace,
         }
     }
 
-    pub fn run(&self, max_casts: Option<i32>, threshold: u32, stop_sender: Sender<()>) {
-        self.find_rod_address();
-        if self.rod_address.lock().is_none() {
-            println!("failed to find fishing rod address");
-            return;
+    pub fn run(&self, config: &FisherConfig) -> i32 {
+        let found = match config.mode {
+            DetectionMode::RodState => self
+                .find_rod_address()
+                .map(|_| ())
+                .map_err(|e| format!("failed to find fishing rod address: {}", e)),
+            DetectionMode::HookDip => self
+                .find_hook_reader()
+                .map_err(|e| format!("failed to find fishing hook: {}", e)),
+        };
+        if let Err(e) = found {
+            if !self.cache.is_alive() {
+                println!("game process exited while resolving offsets");
+                self.process_exited.store(true, Ordering::Relaxed);
+                return 0;
+            }
+            println!("{}", e);
+            *self.last_error.lock() = Some(e);
+            return 0;
         }
 
-        println!("starting fishing loop in 5 seconds...");
-        thread::sleep(INITIAL_DELAY);
+        println!(
+            "starting fishing loop in {:.1} seconds...",
+            config.initial_delay().as_secs_f32()
+        );
+        thread::sleep(config.initial_delay());
 
         println!("fishing started!");
         let mut cast_count = 0;
-        while !self.should_stop.load(Ordering::Relaxed) {
-            self.fish_cycle(threshold);
+        while self.is_running() {
+            match config.mode {
+                DetectionMode::RodState => self.fish_cycle(config, config.detector.build()),
+                DetectionMode::HookDip => self.hook_cycle(config),
+            }
             cast_count += 1;
 
-            if let Some(max) = max_casts {
+            if let Some(max) = config.max_casts {
                 if cast_count >= max {
                     println!("reached maximum number of casts");
                     break;
@@ -59,164 +248,425 @@ impl Fisher {
             }
         }
         println!("fishing stopped");
-        let _ = stop_sender.send(());
+        cast_count
     }
 
     pub fn stop(&self) {
         self.should_stop.store(true, Ordering::Relaxed);
     }
 
-    fn find_rod_address(&self) {
-        if let Ok(base) = self.memory.get_module_base("Minecraft.Windows.exe") {
-            let absolute_base = base + BASE_ADDRESS;
-            if let Ok(address) = self.memory.follow_pointers(absolute_base, OFFSETS) {
-                let mut rod_address = self.rod_address.lock();
+    pub fn process_exited(&self) -> bool {
+        self.process_exited.load(Ordering::Relaxed)
+    }
+
+    fn is_running(&self) -> bool {
+        if self.should_stop.load(Ordering::Relaxed) || self.process_exited() {
+            return false;
+        }
+        if !self.cache.is_alive() {
+            println!("game process exited");
+            self.process_exited.store(true, Ordering::Relaxed);
+            return false;
+        }
+        true
+    }
+
+    pub fn last_error(&self) -> Option<String> {
+        self.last_error.lock().clone()
+    }
+
+    pub fn last_trace(&self) -> Option<Arc<PointerTrace>> {
+        self.last_trace.lock().clone()
+    }
+
+    fn find_rod_address(&self) -> Result<Address, String> {
+        let memory = self.cache.inner();
+        let (entry, module) = self.offsets.resolve(memory)?;
+        if entry.is_fallback() {
+            println!(
+                "using fallback offsets '{}', build not verified",
+                entry.name
+            );
+        } else {
+            println!("using offsets '{}'", entry.name);
+        }
+
+        let base = entry.static_base(memory, &module);
+        let trace = Arc::new(memory.trace_pointers(base, &entry.offsets));
+        print!("{}", trace);
+        *self.last_trace.lock() = Some(trace.clone());
+
+        let address = trace
+            .address()
+            .ok_or_else(|| trace.failure().unwrap_or_default())?;
+        *self.rod_address.lock() = Some(address);
+        *self.rod_chain.lock() = Some((base, entry.offsets.clone()));
+        println!("fishing rod address found: 0x{:X}", address);
+        Ok(address)
+    }
+
+    fn find_hook_reader(&self) -> Result<(), String> {
+        let memory = self.cache.inner();
+        let (entry, module) = self.offsets.resolve(memory)?;
+        let fields = entry
+            .hook
+            .clone()
+            .ok_or_else(|| format!("offsets entry '{}' has no hook table", entry.name))?;
+        *self.hook_reader.lock() = Some(HookReader::new(memory, module, fields)?);
+        Ok(())
+    }
+
+    fn verify_rod_address(&self) {
+        let (base, offsets) = match self.rod_chain.lock().clone() {
+            Some(chain) => chain,
+            None => return,
+        };
+
+        if let Ok(address) = self.cache.follow_pointers(base, &offsets) {
+            let mut rod_address = self.rod_address.lock();
+            if *rod_address != Some(address) {
+                println!("fishing rod address moved to 0x{:X}", address);
                 *rod_address = Some(address);
-                println!("fishing rod address found: 0x{:X}", address);
-            } else {
-                println!("failed to follow pointers for fishing rod address");
             }
-        } else {
-            println!("failed to get module base for Minecraft.Windows.exe");
         }
     }
 
-    fn fish_cycle(&self, threshold: u32) {
+    fn recover_rod_address(&self) {
+        println!("fishing rod address looks stale, resolving it again...");
+        *self.rod_address.lock() = None;
+
+        while self.is_running() {
+            match self.find_rod_address() {
+                Ok(_) => return,
+                Err(e) => {
+                    println!("failed to find fishing rod address: {}", e);
+                    thread::sleep(RESOLVE_RETRY_INTERVAL);
+                }
+            }
+        }
+    }
+
+    fn fish_cycle(&self, config: &FisherConfig, mut detector: Box<dyn BiteDetector>) {
         let mut state = FishingState::Casting;
-        let mut peak_value = 0;
-        let mut last_value = 0;
-        let mut falling_count = 0;
-        let mut stable_count = 0;
-        let mut post_peak_count = 0;
+        let mut read_failures = 0;
+        let mut first_value = None;
+        let mut changed_since_cast = false;
+        let mut stale = false;
+
+        self.cache.invalidate();
+        self.verify_rod_address();
+        let cast_value = self.get_rod_state().ok();
         let start_time = Instant::now();
 
-        self.cast();
-        thread::sleep(CAST_DELAY);
-
-        while state != FishingState::Reeling && !self.should_stop.load(Ordering::Relaxed) {
-            if let Some(current_value) = self.get_rod_state() {
-                println!("current rod state: {}", current_value);
-
-                state = self.update_fishing_state(
-                    state,
-                    current_value,
-                    &mut peak_value,
-                    &mut last_value,
-                    &mut falling_count,
-                    &mut stable_count,
-                    &mut post_peak_count,
-                    threshold,
-                );
+        self.cast(config);
+        thread::sleep(config.cast_delay());
+
+        while state != FishingState::Reeling && self.is_running() {
+            match self.get_rod_state() {
+                Ok(current_value) if current_value > MAX_ROD_STATE => {
+                    println!("rod state {} is out of range", current_value);
+                    stale = true;
+                    break;
+                }
+                Ok(current_value) => {
+                    println!("current rod state: {}", current_value);
+                    self.record(TraceEvent::Sample(current_value));
+                    read_failures = 0;
+                    let reference = *first_value.get_or_insert(cast_value.unwrap_or(current_value));
+                    changed_since_cast |= current_value != reference;
+
+                    let sample = Sample {
+                        time: start_time.elapsed(),
+                        value: current_value,
+                    };
+                    state = self.update_fishing_state(state, sample, detector.as_mut());
+                }
+                Err(e) => {
+                    read_failures += 1;
+                    if read_failures >= MAX_READ_FAILURES {
+                        println!("failed to read rod state: {}", e);
+                        stale = true;
+                        break;
+                    }
+                }
+            }
+
+            if !changed_since_cast && start_time.elapsed() > STUCK_TIMEOUT {
+                println!("rod state did not change after casting");
+                stale = true;
+                break;
             }
 
-            if self.is_timeout(start_time) {
+            if start_time.elapsed() > config.bite_timeout() {
                 println!("timeout reached, recasting...");
+                self.record(TraceEvent::Timeout);
                 break;
             }
 
-            thread::sleep(POLL_INTERVAL);
+            thread::sleep(config.poll_interval());
         }
 
         if state == FishingState::Reeling {
-            self.reel();
+            self.reel(config);
+        } else if stale {
+            self.recover_rod_address();
         }
 
-        thread::sleep(Duration::from_secs(1));
+        thread::sleep(config.recast_delay());
+    }
+
+    fn hook_cycle(&self, config: &FisherConfig) {
+        let reader = self.hook_reader.lock();
+        let reader = match reader.as_ref() {
+            Some(reader) => reader,
+            None => return,
+        };
+        let mut detector = DipDetector::default();
+        let mut read_failures = 0;
+        let mut bite = false;
+        let start_time = Instant::now();
+
+        self.cast(config);
+        thread::sleep(config.cast_delay());
+
+        let mut hook = self.locate_hook(reader);
+        let mut last_scan = Instant::now();
+
+        while self.is_running() {
+            self.cache.invalidate();
+            match hook {
+                Some(address) => match reader.read(&self.cache, address) {
+                    Ok(state) => {
+                        println!(
+                            "hook at {:.2} {:.2} {:.2}, vertical motion {:.3}, in water: {}",
+                            state.position.x,
+                            state.position.y,
+                            state.position.z,
+                            state.motion.y,
+                            state.in_water
+                        );
+                        read_failures = 0;
+                        if detector.update(&state) {
+                            bite = true;
+                            break;
+                        }
+                    }
+                    Err(e) => {
+                        read_failures += 1;
+                        if read_failures >= MAX_READ_FAILURES {
+                            println!("failed to read fishing hook: {}", e);
+                            break;
+                        }
+                        if read_failures % HOOK_RESCAN_FAILURES == 0 {
+                            hook = self.locate_hook(reader);
+                            last_scan = Instant::now();
+                        }
+                    }
+                },
+                None if start_time.elapsed() > STUCK_TIMEOUT => {
+                    println!("no fishing hook appeared after casting");
+                    break;
+                }
+                None if last_scan.elapsed() > HOOK_RESCAN_INTERVAL => {
+                    hook = self.locate_hook(reader);
+                    last_scan = Instant::now();
+                }
+                None => {}
+            }
+
+            if start_time.elapsed() > config.bite_timeout() {
+                println!("timeout reached, recasting...");
+                self.record(TraceEvent::Timeout);
+                break;
+            }
+
+            thread::sleep(config.poll_interval());
+        }
+
+        if bite {
+            thread::sleep(config.hook_reel_delay());
+            self.reel(config);
+        }
+
+        thread::sleep(config.recast_delay());
+    }
+
+    fn locate_hook(&self, reader: &HookReader) -> Option<Address> {
+        let memory = self.cache.inner();
+        let last = *self.last_hook.lock();
+        let nearby = last
+            .and_then(|address| reader.find_hooks_near(memory, address).ok())
+            .and_then(|hooks| hooks.into_iter().find(|&hook| Some(hook) != last));
+        let hook = match nearby {
+            Some(hook) => Some(hook),
+            None => new_hook(&reader.find_hooks(memory).ok()?, last),
+        };
+        if let Some(address) = hook {
+            println!("fishing hook found at 0x{:X}", address);
+            *self.last_hook.lock() = Some(address);
+        }
+        hook
     }
 
     fn update_fishing_state(
         &self,
         state: FishingState,
-        current_value: u32,
-        peak_value: &mut u32,
-        last_value: &mut u32,
-        falling_count: &mut u32,
-        stable_count: &mut u32,
-        post_peak_count: &mut u32,
-        threshold: u32,
+        sample: Sample,
+        detector: &mut dyn BiteDetector,
     ) -> FishingState {
         match state {
             FishingState::Casting => {
-                if current_value > 0 {
+                if sample.value > 0 {
                     println!("waiting for bite...");
                     FishingState::WaitingForBite
                 } else {
                     state
                 }
             }
-            FishingState::WaitingForBite => self.update_bite_detection(
-                current_value,
-                peak_value,
-                last_value,
-                falling_count,
-                stable_count,
-                post_peak_count,
-                threshold,
-            ),
-            FishingState::Reeling => state,
+            FishingState::WaitingForBite if detector.update(sample) => FishingState::Reeling,
+            FishingState::WaitingForBite | FishingState::Reeling => state,
         }
     }
 
-    fn update_bite_detection(
-        &self,
-        current_value: u32,
-        peak_value: &mut u32,
-        last_value: &mut u32,
-        falling_count: &mut u32,
-        stable_count: &mut u32,
-        post_peak_count: &mut u32,
-        threshold: u32,
-    ) -> FishingState {
-        const STABILITY_THRESHOLD: u32 = 3;
-        const MIN_PEAK_VALUE: u32 = 5;
-
-        if current_value > *peak_value {
-            *peak_value = current_value;
-            *falling_count = 0;
-            *stable_count = 0;
-            *post_peak_count = 0;
-        } else if current_value < *last_value {
-            *falling_count += 1;
-            *stable_count = 0;
-            if *falling_count > 2 && *peak_value >= MIN_PEAK_VALUE {
-                *post_peak_count += 1;
-                if *post_peak_count > threshold {
-                    return FishingState::Reeling;
-                }
-            }
-        } else if current_value == *last_value {
-            *stable_count += 1;
-            if *stable_count >= STABILITY_THRESHOLD && *peak_value >= MIN_PEAK_VALUE {
-                *post_peak_count += 1;
-                if *post_peak_count > threshold {
-                    return FishingState::Reeling;
-                }
-            }
-        } else {
-            *falling_count = 0;
-            *stable_count = 0;
+    fn record(&self, event: TraceEvent) {
+        if let Some(trace) = &self.trace {
+            trace.record(event);
         }
-        *last_value = current_value;
-        FishingState::WaitingForBite
     }
 
-    fn is_timeout(&self, start_time: Instant) -> bool {
-        start_time.elapsed() > Duration::from_secs(60)
-    }
-
-    fn cast(&self) {
+    fn cast(&self, config: &FisherConfig) {
         println!("casting rod...");
-        input::send_key(Key::MouseRight);
+        self.record(TraceEvent::Cast);
+        input::send_key(Key::MouseRight, config.key_hold());
     }
 
-    fn reel(&self) {
+    fn reel(&self, config: &FisherConfig) {
         println!("fish on, reeling in...");
-        input::send_key(Key::MouseRight);
+        self.record(TraceEvent::Reel);
+        input::send_key(Key::MouseRight, config.key_hold());
     }
 
-    fn get_rod_state(&self) -> Option<u32> {
-        self.rod_address
+    fn get_rod_state(&self) -> Result<u32, MemoryError> {
+        let address = self
+            .rod_address
             .lock()
-            .and_then(|addr| self.memory.read::<u32>(addr).ok())
+            .ok_or_else(|| MemoryError::NotFound("fishing rod address".to_string()))?;
+        self.cache.inner().read_le::<u32>(address)
+    }
+}
+
+fn new_hook(hooks: &[Address], last: Option<Address>) -> Option<Address> {
+    match hooks.iter().find(|&&hook| Some(hook) != last) {
+        Some(&hook) => Some(hook),
+        None if hooks.len() == 1 => Some(hooks[0]),
+        None => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::FakeMemory;
+    use crate::trace::Trace;
+
+    const ROD_STATE: Address = 0x3000_000C;
+
+    fn test_config() -> FisherConfig {
+        FisherConfig {
+            initial_delay_ms: 0,
+            cast_delay_ms: 0,
+            poll_interval_ms: 10,
+            bite_timeout_ms: 5_000,
+            recast_delay_ms: 0,
+            ..Default::default()
+        }
+    }
+
+    fn fisher(memory: FakeMemory, name: &str) -> (Fisher<FakeMemory>, PathBuf) {
+        let path = std::env::temp_dir().join(format!(
+            "mcbe-fisher-test-{}-{}.txt",
+            name,
+            std::process::id()
+        ));
+        let trace = TraceRecorder::create(&path).unwrap();
+        let fisher = Fisher::new(
+            Arc::new(memory),
+            OffsetDatabase::default(),
+            Some(Arc::new(trace)),
+        );
+        *fisher.rod_address.lock() = Some(ROD_STATE);
+        (fisher, path)
+    }
+
+    fn events(path: &Path) -> Vec<TraceEvent> {
+        let trace = Trace::load(path).unwrap();
+        let _ = fs::remove_file(path);
+        trace.events.into_iter().map(|(_, event)| event).collect()
+    }
+
+    #[test]
+    fn reels_in_after_a_bite() {
+        let memory = FakeMemory::new();
+        memory.script(ROD_STATE, &[0u32, 0, 3, 6, 9, 12, 12, 12, 12, 12]);
+        let (fisher, path) = fisher(memory, "bite");
+        let config = test_config();
+
+        fisher.fish_cycle(&config, config.detector.build());
+
+        let events = events(&path);
+        assert_eq!(events.first(), Some(&TraceEvent::Cast));
+        assert_eq!(events.last(), Some(&TraceEvent::Reel));
+        assert!(events.contains(&TraceEvent::Sample(12)));
+    }
+
+    #[test]
+    fn recasts_without_reeling_on_timeout() {
+        let memory = FakeMemory::new();
+        memory.script(ROD_STATE, &[0u32, 0, 1, 2, 3, 4]);
+        let (fisher, path) = fisher(memory, "timeout");
+        let config = FisherConfig {
+            bite_timeout_ms: 100,
+            ..test_config()
+        };
+
+        fisher.fish_cycle(&config, config.detector.build());
+
+        let events = events(&path);
+        assert_eq!(events.last(), Some(&TraceEvent::Timeout));
+        assert!(!events.contains(&TraceEvent::Reel));
+    }
+
+    #[test]
+    fn validates_the_detector() {
+        assert!(FisherConfig::default().validate().is_ok());
+
+        let mut config = FisherConfig::default();
+        config.detector.edge.min_rate = -1.0;
+        assert!(config.validate().unwrap_err().contains("edge.min_rate"));
+
+        let mut config = FisherConfig::default();
+        config.detector.moving_average.fall = f32::NAN;
+        assert!(config.validate().is_err());
+
+        let mut config = FisherConfig::default();
+        config.detector.change_point.threshold = f32::INFINITY;
+        assert!(config.validate().is_err());
+
+        let config = FisherConfig {
+            hook_reel_delay_ms: 10_000,
+            ..Default::default()
+        };
+        assert!(config
+            .validate()
+            .unwrap_err()
+            .contains("hook_reel_delay_ms"));
+    }
+
+    #[test]
+    fn prefers_a_hook_other_than_the_last_one() {
+        assert_eq!(new_hook(&[0x10, 0x20], Some(0x10)), Some(0x20));
+        assert_eq!(new_hook(&[0x10], Some(0x10)), Some(0x10));
+        assert_eq!(new_hook(&[0x10, 0x20], None), Some(0x10));
+        assert_eq!(new_hook(&[], None), None);
     }
 }
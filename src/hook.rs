@@ -0,0 +1,181 @@
+use crate::memory::{self, Address, MemoryError, ModuleInfo, ProcessMemory, Vec3};
+use crate::offsets::HookEntry;
+use crate::rtti::{self, RttiClass};
+use crate::signature::ModuleImage;
+
+const MAX_HOOKS: usize = 16;
+const DIP_MOTION: f32 = 0.1;
+const DIP_DEPTH: f32 = 0.15;
+const SETTLED_MOTION: f32 = 0.02;
+const SETTLE_WEIGHT: f32 = 0.2;
+const SETTLE_SAMPLES: usize = 5;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct HookState {
+    pub position: Vec3,
+    pub motion: Vec3,
+    pub in_water: bool,
+}
+
+pub struct HookReader {
+    module: ModuleInfo,
+    vtable: Address,
+    fields: HookEntry,
+}
+
+#[derive(Default)]
+pub struct DipDetector {
+    resting: Option<f32>,
+    settled: usize,
+}
+
+impl HookReader {
+    pub fn new<M: ProcessMemory>(
+        memory: &M,
+        module: ModuleInfo,
+        fields: HookEntry,
+    ) -> Result<Self, String> {
+        let image = ModuleImage::read(memory, &module);
+        let class = RttiClass::find(&image, &fields.class)
+            .ok_or_else(|| format!("no type descriptor for {} in {}", fields.class, module.name))?;
+        let vtable = class
+            .primary_vtable()
+            .ok_or_else(|| format!("no vtable found for {}", fields.class))?;
+        println!(
+            "{} vtable found at {}+0x{:X}",
+            fields.class,
+            module.name,
+            vtable - module.base
+        );
+
+        Ok(HookReader {
+            module,
+            vtable,
+            fields,
+        })
+    }
+
+    pub fn find_hooks<M: ProcessMemory>(&self, memory: &M) -> memory::Result<Vec<Address>> {
+        rtti::find_objects(memory, &self.module, self.vtable, MAX_HOOKS)
+    }
+
+    pub fn find_hooks_near<M: ProcessMemory>(
+        &self,
+        memory: &M,
+        hook: Address,
+    ) -> memory::Result<Vec<Address>> {
+        let region = match memory.region_at(hook)? {
+            Some(region) if region.is_readable() => region,
+            _ => return Ok(Vec::new()),
+        };
+        let ranges = std::iter::once(region.base..region.end());
+        Ok(rtti::find_objects_in(
+            memory,
+            &self.module,
+            ranges,
+            self.vtable,
+            MAX_HOOKS,
+        ))
+    }
+
+    pub fn read<M: ProcessMemory>(&self, memory: &M, hook: Address) -> memory::Result<HookState> {
+        if memory.read_le::<usize>(hook)? != self.vtable {
+            return Err(MemoryError::NotFound(format!(
+                "{} at 0x{:X}",
+                self.fields.class, hook
+            )));
+        }
+
+        Ok(HookState {
+            position: memory.read_le::<Vec3>(field(memory, hook, &self.fields.position)?)?,
+            motion: memory.read_le::<Vec3>(field(memory, hook, &self.fields.motion)?)?,
+            in_water: memory.read_le::<u8>(field(memory, hook, &self.fields.in_water)?)? != 0,
+        })
+    }
+}
+
+impl DipDetector {
+    pub fn update(&mut self, state: &HookState) -> bool {
+        if !state.in_water {
+            *self = DipDetector::default();
+            return false;
+        }
+
+        let y = state.position.y;
+        let still = state.motion.y.abs() < SETTLED_MOTION;
+        if self.settled < SETTLE_SAMPLES {
+            self.settled = if still { self.settled + 1 } else { 0 };
+            self.resting = Some(y);
+            return false;
+        }
+
+        let resting = self.resting.unwrap_or(y);
+        if state.motion.y < -DIP_MOTION || y < resting - DIP_DEPTH {
+            return true;
+        }
+
+        if still {
+            self.resting = Some(resting + (y - resting) * SETTLE_WEIGHT);
+        }
+        false
+    }
+}
+
+fn field<M: ProcessMemory>(memory: &M, hook: Address, path: &[usize]) -> memory::Result<Address> {
+    match path.split_first() {
+        Some((&offset, rest)) => memory.follow_pointers(hook + offset, rest),
+        None => Ok(hook),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(y: f32, motion: f32, in_water: bool) -> HookState {
+        HookState {
+            position: Vec3 { x: 0.0, y, z: 0.0 },
+            motion: Vec3 {
+                x: 0.0,
+                y: motion,
+                z: 0.0,
+            },
+            in_water,
+        }
+    }
+
+    fn settle(detector: &mut DipDetector) {
+        for _ in 0..SETTLE_SAMPLES {
+            assert!(!detector.update(&state(62.0, 0.0, true)));
+        }
+    }
+
+    #[test]
+    fn ignores_the_splash() {
+        let mut detector = DipDetector::default();
+        assert!(!detector.update(&state(64.0, -0.4, false)));
+        for (y, motion) in [(62.5, -0.3), (61.8, 0.2), (62.2, -0.15), (61.9, 0.05)] {
+            assert!(!detector.update(&state(y, motion, true)));
+        }
+    }
+
+    #[test]
+    fn detects_a_dip_after_settling() {
+        let mut detector = DipDetector::default();
+        settle(&mut detector);
+        assert!(!detector.update(&state(62.01, 0.01, true)));
+        assert!(detector.update(&state(61.9, -0.2, true)));
+
+        let mut detector = DipDetector::default();
+        settle(&mut detector);
+        assert!(detector.update(&state(61.8, -0.05, true)));
+    }
+
+    #[test]
+    fn rearms_after_leaving_the_water() {
+        let mut detector = DipDetector::default();
+        settle(&mut detector);
+        assert!(!detector.update(&state(63.0, 0.3, false)));
+        assert!(!detector.update(&state(61.5, -0.3, true)));
+    }
+}
@@ -1,11 +1,13 @@
-use rdev::{simulate, Button, EventType};
+#[cfg(not(test))]
+use rdev::simulate;
+use rdev::{Button, EventType};
 use std::{thread, time::Duration};
 
 pub enum Key {
     MouseRight,
 }
 
-pub fn send_key(key: Key) {
+pub fn send_key(key: Key, hold: Duration) {
     let event = match key {
         Key::MouseRight => EventType::ButtonPress(Button::Right),
     };
@@ -14,7 +16,7 @@ pub fn send_key(key: Key) {
         println!("failed to send key event: {:?}", e);
     }
 
-    thread::sleep(Duration::from_millis(50));
+    thread::sleep(hold);
 
     let release = match key {
         Key::MouseRight => EventType::ButtonRelease(Button::Right),
@@ -24,3 +26,8 @@ pub fn send_key(key: Key) {
         println!("failed to send key release event: {:?}", e);
     }
 }
+
+#[cfg(test)]
+fn simulate(_event: &EventType) -> Result<(), rdev::SimulateError> {
+    Ok(())
+}
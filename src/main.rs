@@ -1,13 +1,37 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod binary;
+mod cli;
+mod detector;
+mod diagnostics;
 mod fisher;
+mod hook;
 mod input;
 mod memory;
+mod offsets;
+mod pe;
+mod pointer_scan;
+mod remote;
+mod rtti;
+mod signature;
+mod supervisor;
+mod trace;
+mod tune;
+mod value_scan;
 mod window;
 
 use window::FisherUi;
 
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        if let Err(e) = cli::run(&args) {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     let app = FisherUi::new();
     if let Err(e) = app.run() {
         eprintln!("error running app: {}", e);
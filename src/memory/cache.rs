@@ -0,0 +1,72 @@
+use super::{Address, MemoryRegion, ModuleInfo, ProcessMemory, Result, PAGE_SIZE};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+pub struct PageCache<M: ProcessMemory> {
+    memory: Arc<M>,
+    pages: Mutex<HashMap<Address, Box<[u8]>>>,
+}
+
+impl<M: ProcessMemory> PageCache<M> {
+    pub fn new(memory: Arc<M>) -> Self {
+        PageCache {
+            memory,
+            pages: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn inner(&self) -> &M {
+        &self.memory
+    }
+
+    pub fn invalidate(&self) {
+        self.pages.lock().clear();
+    }
+}
+
+impl<M: ProcessMemory> ProcessMemory for PageCache<M> {
+    fn read_raw(&self, address: Address, buffer: &mut [u8]) -> Result<()> {
+        if buffer.len() >= PAGE_SIZE {
+            return self.memory.read_raw(address, buffer);
+        }
+
+        let mut pages = self.pages.lock();
+        let mut filled = 0;
+        while filled < buffer.len() {
+            let current = address + filled;
+            let page_base = current & !(PAGE_SIZE - 1);
+            if !pages.contains_key(&page_base) {
+                let mut page = vec![0u8; PAGE_SIZE].into_boxed_slice();
+                if self.memory.read_raw(page_base, &mut page).is_err() {
+                    drop(pages);
+                    return self.memory.read_raw(address, buffer);
+                }
+                pages.insert(page_base, page);
+            }
+
+            let page = &pages[&page_base];
+            let offset = current - page_base;
+            let len = (PAGE_SIZE - offset).min(buffer.len() - filled);
+            buffer[filled..filled + len].copy_from_slice(&page[offset..offset + len]);
+            filled += len;
+        }
+        Ok(())
+    }
+
+    fn modules(&self) -> Result<Vec<ModuleInfo>> {
+        self.memory.modules()
+    }
+
+    fn regions(&self) -> Result<Vec<MemoryRegion>> {
+        self.memory.regions()
+    }
+
+    fn region_at(&self, address: Address) -> Result<Option<MemoryRegion>> {
+        self.memory.region_at(address)
+    }
+
+    fn is_alive(&self) -> bool {
+        self.memory.is_alive()
+    }
+}
@@ -0,0 +1,88 @@
+use super::{Address, MemoryError};
+use std::fmt;
+
+#[derive(Clone, Debug)]
+pub struct PointerHop {
+    pub address: Address,
+    pub value: Option<Address>,
+    pub offset: usize,
+}
+
+#[derive(Debug)]
+pub struct PointerTrace {
+    pub base: Address,
+    pub hops: Vec<PointerHop>,
+    pub error: Option<MemoryError>,
+}
+
+impl PointerHop {
+    pub fn next(&self) -> Option<Address> {
+        self.value.map(|value| value.wrapping_add(self.offset))
+    }
+}
+
+impl PointerTrace {
+    pub fn failed_at(&self) -> Option<usize> {
+        self.error.as_ref().map(|_| self.hops.len() - 1)
+    }
+
+    pub fn address(&self) -> Option<Address> {
+        match self.error {
+            Some(_) => None,
+            None => Some(
+                self.hops
+                    .last()
+                    .and_then(PointerHop::next)
+                    .unwrap_or(self.base),
+            ),
+        }
+    }
+
+    pub fn addresses(&self) -> Vec<Address> {
+        self.hops
+            .iter()
+            .map(|hop| hop.address)
+            .chain(self.address())
+            .collect()
+    }
+
+    pub fn failure(&self) -> Option<String> {
+        let hop = self.failed_at()?;
+        let error = self.error.as_ref()?;
+        Some(format!(
+            "failed to follow pointers at hop {} (offset 0x{:X}): {}",
+            hop,
+            self.hops[hop].offset,
+            error.explain()
+        ))
+    }
+}
+
+impl fmt::Display for PointerTrace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (index, hop) in self.hops.iter().enumerate() {
+            match hop.next() {
+                Some(next) => writeln!(
+                    f,
+                    "[{}] [0x{:X}] -> 0x{:X} + 0x{:X} = 0x{:X}",
+                    index,
+                    hop.address,
+                    hop.value.unwrap_or_default(),
+                    hop.offset,
+                    next
+                )?,
+                None => writeln!(
+                    f,
+                    "[{}] [0x{:X}] failed: {}",
+                    index,
+                    hop.address,
+                    self.error
+                        .as_ref()
+                        .map(MemoryError::to_string)
+                        .unwrap_or_default()
+                )?,
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,75 @@
+use super::Address;
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum MemoryError {
+    AccessDenied,
+    ProcessGone,
+    PartialCopy(Address),
+    InvalidAddress(Address),
+    Protected(Address),
+    NotFound(String),
+    InvalidData(String),
+    Io(io::Error),
+}
+
+impl MemoryError {
+    pub fn hint(&self) -> &'static str {
+        match self {
+            MemoryError::AccessDenied if cfg!(windows) => "try running the bot as administrator",
+            MemoryError::AccessDenied => {
+                "try running the bot as root or setting kernel.yama.ptrace_scope to 0"
+            }
+            MemoryError::ProcessGone => "the game was closed, start it again",
+            MemoryError::PartialCopy(_)
+            | MemoryError::InvalidAddress(_)
+            | MemoryError::Protected(_) => {
+                "the offsets are probably outdated for this game version"
+            }
+            MemoryError::NotFound(_) => "check the process and module names",
+            MemoryError::InvalidData(_) | MemoryError::Io(_) => "",
+        }
+    }
+
+    pub fn explain(&self) -> String {
+        match self.hint() {
+            "" => self.to_string(),
+            hint => format!("{} ({})", self, hint),
+        }
+    }
+}
+
+impl fmt::Display for MemoryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MemoryError::AccessDenied => write!(f, "access to the game process was denied"),
+            MemoryError::ProcessGone => write!(f, "the game process has exited"),
+            MemoryError::PartialCopy(address) => {
+                write!(
+                    f,
+                    "only part of the memory at 0x{:X} could be read",
+                    address
+                )
+            }
+            MemoryError::InvalidAddress(address) => write!(f, "invalid address 0x{:X}", address),
+            MemoryError::Protected(address) => {
+                write!(f, "memory at 0x{:X} is not readable", address)
+            }
+            MemoryError::NotFound(what) => write!(f, "{} not found", what),
+            MemoryError::InvalidData(what) => write!(f, "invalid data: {}", what),
+            MemoryError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for MemoryError {}
+
+impl From<io::Error> for MemoryError {
+    fn from(e: io::Error) -> Self {
+        match e.kind() {
+            io::ErrorKind::PermissionDenied => MemoryError::AccessDenied,
+            _ => MemoryError::Io(e),
+        }
+    }
+}
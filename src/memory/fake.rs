@@ -0,0 +1,130 @@
+use super::{
+    Address, MemoryError, MemoryRegion, ModuleInfo, ProcessMemory, Protection, RegionState,
+    RegionType, Result,
+};
+use bytemuck::Pod;
+use parking_lot::Mutex;
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+#[derive(Default)]
+pub struct FakeMemory {
+    blocks: Mutex<HashMap<Address, Vec<u8>>>,
+    scripts: Mutex<HashMap<Address, VecDeque<Vec<u8>>>>,
+    modules: Vec<ModuleInfo>,
+    exited: AtomicBool,
+}
+
+impl FakeMemory {
+    pub fn new() -> Self {
+        FakeMemory::default()
+    }
+
+    pub fn add_module(&mut self, name: &str, base: Address, size: usize) {
+        self.write(base, &vec![0u8; size]);
+        self.modules.push(ModuleInfo {
+            name: name.to_string(),
+            base,
+            size,
+            path: PathBuf::from(name),
+        });
+    }
+
+    pub fn write(&self, address: Address, bytes: &[u8]) {
+        let mut blocks = self.blocks.lock();
+        let block = blocks
+            .iter_mut()
+            .find(|(&base, block)| base <= address && address + bytes.len() <= base + block.len());
+        match block {
+            Some((&base, block)) => {
+                let offset = address - base;
+                block[offset..offset + bytes.len()].copy_from_slice(bytes);
+            }
+            None => {
+                blocks.insert(address, bytes.to_vec());
+            }
+        }
+    }
+
+    pub fn put<T: Pod>(&self, address: Address, value: T) {
+        self.write(address, bytemuck::bytes_of(&value));
+    }
+
+    pub fn script<T: Pod>(&self, address: Address, values: &[T]) {
+        let values = values
+            .iter()
+            .map(|value| bytemuck::bytes_of(value).to_vec())
+            .collect();
+        self.scripts.lock().insert(address, values);
+    }
+
+    pub fn exit(&self) {
+        self.exited.store(true, Ordering::Relaxed);
+    }
+
+    fn advance_scripts(&self, range: std::ops::Range<Address>) {
+        let mut scripts = self.scripts.lock();
+        for (&address, values) in scripts.iter_mut() {
+            if !range.contains(&address) {
+                continue;
+            }
+            let value = match values.len() {
+                0 => continue,
+                1 => values[0].clone(),
+                _ => values.pop_front().unwrap(),
+            };
+            self.write(address, &value);
+        }
+    }
+}
+
+impl ProcessMemory for FakeMemory {
+    fn read_raw(&self, address: Address, buffer: &mut [u8]) -> Result<()> {
+        if !self.is_alive() {
+            return Err(MemoryError::ProcessGone);
+        }
+        self.advance_scripts(address..address + buffer.len());
+        let blocks = self.blocks.lock();
+        let (base, block) = blocks
+            .iter()
+            .find(|(&base, block)| base <= address && address + buffer.len() <= base + block.len())
+            .ok_or(MemoryError::InvalidAddress(address))?;
+        let offset = address - base;
+        buffer.copy_from_slice(&block[offset..offset + buffer.len()]);
+        Ok(())
+    }
+
+    fn modules(&self) -> Result<Vec<ModuleInfo>> {
+        if !self.is_alive() {
+            return Err(MemoryError::ProcessGone);
+        }
+        Ok(self.modules.clone())
+    }
+
+    fn regions(&self) -> Result<Vec<MemoryRegion>> {
+        let mut regions: Vec<MemoryRegion> = self
+            .blocks
+            .lock()
+            .iter()
+            .map(|(&base, block)| MemoryRegion {
+                base,
+                size: block.len(),
+                state: RegionState::Committed,
+                protection: Protection {
+                    read: true,
+                    write: true,
+                    execute: false,
+                    guard: false,
+                },
+                kind: RegionType::Private,
+            })
+            .collect();
+        regions.sort_by_key(|region| region.base);
+        Ok(regions)
+    }
+
+    fn is_alive(&self) -> bool {
+        !self.exited.load(Ordering::Relaxed)
+    }
+}
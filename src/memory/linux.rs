@@ -0,0 +1,274 @@
+use super::{
+    Address, MemoryError, MemoryRegion, ModuleInfo, ProcessInfo, ProcessMemory, Protection,
+    RegionState, RegionType, Result,
+};
+use parking_lot::Mutex;
+use std::fs::{self, File};
+use std::io::{self, ErrorKind};
+use std::os::raw::{c_int, c_long};
+use std::os::unix::fs::FileExt;
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const REGION_CACHE_TTL: Duration = Duration::from_millis(500);
+const DEFAULT_CLOCK_TICKS: u64 = 100;
+const SC_CLK_TCK: c_int = 2;
+
+const EPERM: i32 = 1;
+const ESRCH: i32 = 3;
+const EIO: i32 = 5;
+const EACCES: i32 = 13;
+const EFAULT: i32 = 14;
+
+extern "C" {
+    fn sysconf(name: c_int) -> c_long;
+}
+
+pub struct MemoryReader {
+    pid: u32,
+    mem: File,
+    regions: Mutex<Option<(Instant, Vec<MemoryRegion>)>>,
+}
+
+impl MemoryReader {
+    pub fn new(pid: u32) -> Result<Self> {
+        let mem = File::open(format!("/proc/{}/mem", pid)).map_err(|e| match e.kind() {
+            ErrorKind::NotFound => MemoryError::ProcessGone,
+            _ => MemoryError::from(e),
+        })?;
+        Ok(MemoryReader {
+            pid,
+            mem,
+            regions: Mutex::new(None),
+        })
+    }
+
+    fn map_error(&self, e: io::Error, address: Address) -> MemoryError {
+        if !self.is_alive() {
+            return MemoryError::ProcessGone;
+        }
+
+        match (e.raw_os_error(), e.kind()) {
+            (Some(EPERM | EACCES), _) => MemoryError::AccessDenied,
+            (Some(ESRCH), _) => MemoryError::ProcessGone,
+            (Some(EIO | EFAULT), _) => MemoryError::InvalidAddress(address),
+            (_, ErrorKind::UnexpectedEof) => MemoryError::PartialCopy(address),
+            _ => MemoryError::Io(e),
+        }
+    }
+
+    fn maps(&self) -> Result<String> {
+        fs::read_to_string(format!("/proc/{}/maps", self.pid)).map_err(|e| {
+            if self.is_alive() {
+                MemoryError::from(e)
+            } else {
+                MemoryError::ProcessGone
+            }
+        })
+    }
+}
+
+impl ProcessMemory for MemoryReader {
+    fn read_raw(&self, address: Address, buffer: &mut [u8]) -> Result<()> {
+        self.mem
+            .read_exact_at(buffer, address as u64)
+            .map_err(|e| self.map_error(e, address))
+    }
+
+    fn modules(&self) -> Result<Vec<ModuleInfo>> {
+        let maps = self.maps()?;
+        let mut modules: Vec<ModuleInfo> = Vec::new();
+
+        for line in maps.lines() {
+            let (fields, path) = match split_maps_line(line) {
+                Some((fields, Some(path))) if path.starts_with('/') => (fields, path),
+                _ => continue,
+            };
+
+            let (start, end) = parse_range(fields[0])?;
+            let path = Path::new(path);
+            match modules.iter_mut().find(|module| module.path == path) {
+                Some(module) => {
+                    let module_end = (module.base + module.size).max(end);
+                    module.base = module.base.min(start);
+                    module.size = module_end - module.base;
+                }
+                None => modules.push(ModuleInfo {
+                    name: path
+                        .file_name()
+                        .map(|name| name.to_string_lossy().into_owned())
+                        .unwrap_or_default(),
+                    base: start,
+                    size: end - start,
+                    path: path.to_path_buf(),
+                }),
+            }
+        }
+
+        Ok(modules)
+    }
+
+    fn regions(&self) -> Result<Vec<MemoryRegion>> {
+        let mut regions = Vec::new();
+
+        for line in self.maps()?.lines() {
+            let (fields, path) = match split_maps_line(line) {
+                Some(line) => line,
+                None => continue,
+            };
+
+            let (start, end) = parse_range(fields[0])?;
+            let kind = match path {
+                Some(path) if path.starts_with('/') && fields[1].ends_with('s') => {
+                    RegionType::Mapped
+                }
+                Some(path) if path.starts_with('/') => RegionType::Image,
+                Some(path) if path.starts_with("[v") => RegionType::Unknown,
+                _ => RegionType::Private,
+            };
+            let mut protection = Protection::from_unix(fields[1]);
+            if kind == RegionType::Unknown {
+                protection.read = false;
+            }
+
+            regions.push(MemoryRegion {
+                base: start,
+                size: end - start,
+                state: RegionState::Committed,
+                protection,
+                kind,
+            });
+        }
+
+        *self.regions.lock() = Some((Instant::now(), regions.clone()));
+        Ok(regions)
+    }
+
+    fn region_at(&self, address: Address) -> Result<Option<MemoryRegion>> {
+        if let Some((read_at, regions)) = self.regions.lock().as_ref() {
+            let cached = regions.iter().find(|region| region.contains(address));
+            if let Some(region) = cached.filter(|_| read_at.elapsed() < REGION_CACHE_TTL) {
+                return Ok(Some(*region));
+            }
+        }
+
+        Ok(self
+            .regions()?
+            .into_iter()
+            .find(|region| region.contains(address)))
+    }
+
+    fn is_alive(&self) -> bool {
+        Path::new(&format!("/proc/{}", self.pid)).exists()
+    }
+}
+
+fn split_maps_line(line: &str) -> Option<([&str; 5], Option<&str>)> {
+    let mut fields = [""; 5];
+    let mut rest = line;
+    for field in fields.iter_mut() {
+        rest = rest.trim_start();
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        *field = &rest[..end];
+        rest = &rest[end..];
+    }
+    if fields[4].is_empty() {
+        return None;
+    }
+
+    let path = rest.trim();
+    Some((fields, (!path.is_empty()).then_some(path)))
+}
+
+fn parse_range(range: &str) -> Result<(Address, Address)> {
+    let invalid = || MemoryError::InvalidData(format!("memory range {}", range));
+    let (start, end) = range.split_once('-').ok_or_else(invalid)?;
+    let start = Address::from_str_radix(start, 16).map_err(|_| invalid())?;
+    let end = Address::from_str_radix(end, 16).map_err(|_| invalid())?;
+    Ok((start, end))
+}
+
+pub fn find_processes(process_name: &str) -> Vec<ProcessInfo> {
+    let entries = match fs::read_dir("/proc") {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+    let boot_time = boot_time();
+
+    entries
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str()?.parse().ok())
+        .filter_map(|pid| process_info(pid, boot_time))
+        .filter(|process| process.name == process_name)
+        .collect()
+}
+
+fn process_info(pid: u32, boot_time: Option<SystemTime>) -> Option<ProcessInfo> {
+    let path = fs::read_link(format!("/proc/{}/exe", pid)).ok();
+    let name = match &path {
+        Some(path) => path.file_name()?.to_string_lossy().into_owned(),
+        None => {
+            let cmdline = fs::read(format!("/proc/{}/cmdline", pid)).ok()?;
+            let arg0 = cmdline.split(|&b| b == 0).next()?;
+            let arg0 = String::from_utf8_lossy(arg0).into_owned();
+            Path::new(&arg0).file_name()?.to_string_lossy().into_owned()
+        }
+    };
+
+    Some(ProcessInfo {
+        pid,
+        name,
+        path,
+        start_time: boot_time.and_then(|boot| start_time(pid, boot)),
+        window_title: None,
+    })
+}
+
+fn boot_time() -> Option<SystemTime> {
+    let stat = fs::read_to_string("/proc/stat").ok()?;
+    let seconds = stat
+        .lines()
+        .find_map(|line| line.strip_prefix("btime "))?
+        .trim()
+        .parse()
+        .ok()?;
+    Some(UNIX_EPOCH + Duration::from_secs(seconds))
+}
+
+fn start_time(pid: u32, boot_time: SystemTime) -> Option<SystemTime> {
+    let stat = fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    let after_comm = &stat[stat.rfind(')')? + 2..];
+    let ticks: u64 = after_comm.split_whitespace().nth(19)?.parse().ok()?;
+    Some(boot_time + Duration::from_millis(ticks * 1000 / clock_ticks()))
+}
+
+fn clock_ticks() -> u64 {
+    match unsafe { sysconf(SC_CLK_TCK) } {
+        ticks if ticks > 0 => ticks as u64,
+        _ => DEFAULT_CLOCK_TICKS,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_spaces_in_mapped_paths() {
+        let line = "7f00a000-7f00b000 r-xp 00001000 08:01 1234    /home/user/My Games/mcpe/libminecraftpe.so";
+        let (fields, path) = split_maps_line(line).unwrap();
+        assert_eq!(
+            fields,
+            ["7f00a000-7f00b000", "r-xp", "00001000", "08:01", "1234"]
+        );
+        assert_eq!(path, Some("/home/user/My Games/mcpe/libminecraftpe.so"));
+        assert_eq!(parse_range(fields[0]).unwrap(), (0x7f00a000, 0x7f00b000));
+    }
+
+    #[test]
+    fn reads_anonymous_mappings() {
+        let (_, path) = split_maps_line("7ffd0000-7ffd1000 rw-p 00000000 00:00 0 ").unwrap();
+        assert_eq!(path, None);
+        assert!(split_maps_line("7ffd0000-7ffd1000 rw-p").is_none());
+    }
+}
@@ -0,0 +1,432 @@
+use super::{
+    Address, MemoryError, MemoryRegion, ModuleInfo, ProcessMemory, Protection, RegionState,
+    RegionType, Result,
+};
+use crate::binary::invalid_data;
+use parking_lot::Mutex;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+const SIGNATURE: &[u8; 4] = b"MDMP";
+const HEADER_SIZE: usize = 32;
+const DIRECTORY_ENTRY_SIZE: usize = 12;
+const MODULE_SIZE: usize = 108;
+const MEMORY_DESCRIPTOR_SIZE: usize = 16;
+const MEMORY_INFO_SIZE: usize = 48;
+const MAX_STREAM_SIZE: usize = 0x400_0000;
+
+const MODULE_LIST_STREAM: u32 = 4;
+const MEMORY_LIST_STREAM: u32 = 5;
+const MEMORY64_LIST_STREAM: u32 = 9;
+const MEMORY_INFO_LIST_STREAM: u32 = 16;
+
+struct MemoryRange {
+    base: Address,
+    size: usize,
+    offset: u64,
+}
+
+pub struct Minidump {
+    file: Mutex<File>,
+    modules: Vec<ModuleInfo>,
+    ranges: Vec<MemoryRange>,
+    regions: Vec<MemoryRegion>,
+}
+
+impl Minidump {
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut file = File::open(path)?;
+        let header = read_at(&mut file, 0, HEADER_SIZE)?;
+        if &header[..4] != SIGNATURE {
+            return Err(invalid_data("not a minidump"));
+        }
+
+        let stream_count = u32_at(&header, 8) as usize;
+        let directory_rva = u32_at(&header, 12) as u64;
+        let directory = read_at(
+            &mut file,
+            directory_rva,
+            stream_count * DIRECTORY_ENTRY_SIZE,
+        )?;
+
+        let mut modules = Vec::new();
+        let mut ranges = Vec::new();
+        let mut regions = Vec::new();
+        for entry in directory.chunks_exact(DIRECTORY_ENTRY_SIZE) {
+            let stream_type = u32_at(entry, 0);
+            let size = u32_at(entry, 4) as usize;
+            let rva = u32_at(entry, 8) as u64;
+
+            match stream_type {
+                MODULE_LIST_STREAM => {
+                    let stream = read_at(&mut file, rva, size)?;
+                    modules = read_modules(&mut file, &stream)?;
+                }
+                MEMORY_LIST_STREAM => {
+                    let stream = read_at(&mut file, rva, size)?;
+                    ranges.extend(read_memory_list(&stream)?);
+                }
+                MEMORY64_LIST_STREAM => {
+                    let stream = read_at(&mut file, rva, size)?;
+                    ranges.extend(read_memory64_list(&stream)?);
+                }
+                MEMORY_INFO_LIST_STREAM => {
+                    let stream = read_at(&mut file, rva, size)?;
+                    regions = read_memory_info_list(&stream)?;
+                }
+                _ => {}
+            }
+        }
+
+        if ranges.is_empty() {
+            return Err(invalid_data("minidump contains no memory"));
+        }
+        ranges.sort_by_key(|range| range.base);
+
+        Ok(Minidump {
+            file: Mutex::new(file),
+            modules,
+            ranges,
+            regions,
+        })
+    }
+
+    fn range_index(&self, address: Address) -> Option<usize> {
+        let index = self
+            .ranges
+            .partition_point(|range| range.base <= address)
+            .checked_sub(1)?;
+        let range = &self.ranges[index];
+        (address < range.base + range.size).then_some(index)
+    }
+}
+
+impl ProcessMemory for Minidump {
+    fn read_raw(&self, address: Address, buffer: &mut [u8]) -> Result<()> {
+        let mut index = self
+            .range_index(address)
+            .ok_or(MemoryError::InvalidAddress(address))?;
+        let mut file = self.file.lock();
+        let mut current = address;
+        let mut filled = 0;
+
+        while filled < buffer.len() {
+            let range = match self.ranges.get(index) {
+                Some(range) if range.base <= current && current < range.base + range.size => range,
+                _ => return Err(MemoryError::PartialCopy(address)),
+            };
+
+            let skip = current - range.base;
+            let len = (range.size - skip).min(buffer.len() - filled);
+            file.seek(SeekFrom::Start(range.offset + skip as u64))?;
+            file.read_exact(&mut buffer[filled..filled + len])?;
+
+            filled += len;
+            current += len;
+            index += 1;
+        }
+        Ok(())
+    }
+
+    fn modules(&self) -> Result<Vec<ModuleInfo>> {
+        Ok(self.modules.clone())
+    }
+
+    fn regions(&self) -> Result<Vec<MemoryRegion>> {
+        if !self.regions.is_empty() {
+            return Ok(self.regions.clone());
+        }
+
+        Ok(self
+            .ranges
+            .iter()
+            .map(|range| MemoryRegion {
+                base: range.base,
+                size: range.size,
+                state: RegionState::Committed,
+                protection: Protection::READ_ONLY,
+                kind: RegionType::Unknown,
+            })
+            .collect())
+    }
+}
+
+fn read_modules(file: &mut File, stream: &[u8]) -> io::Result<Vec<ModuleInfo>> {
+    if stream.len() < 4 {
+        return Err(invalid_data("truncated minidump stream"));
+    }
+    let count = u32_at(stream, 0) as usize;
+    let entries = stream
+        .get(4..4 + count * MODULE_SIZE)
+        .ok_or_else(|| invalid_data("truncated module list"))?;
+
+    entries
+        .chunks_exact(MODULE_SIZE)
+        .map(|entry| {
+            let path = read_string(file, u32_at(entry, 20) as u64)?;
+            Ok(ModuleInfo {
+                name: path
+                    .rsplit(['\\', '/'])
+                    .next()
+                    .unwrap_or_default()
+                    .to_string(),
+                base: u64_at(entry, 0) as Address,
+                size: u32_at(entry, 8) as usize,
+                path: PathBuf::from(path),
+            })
+        })
+        .collect()
+}
+
+fn read_memory_list(stream: &[u8]) -> io::Result<Vec<MemoryRange>> {
+    if stream.len() < 4 {
+        return Err(invalid_data("truncated minidump stream"));
+    }
+    let count = u32_at(stream, 0) as usize;
+    let entries = stream
+        .get(4..4 + count * MEMORY_DESCRIPTOR_SIZE)
+        .ok_or_else(|| invalid_data("truncated memory list"))?;
+
+    Ok(entries
+        .chunks_exact(MEMORY_DESCRIPTOR_SIZE)
+        .map(|entry| MemoryRange {
+            base: u64_at(entry, 0) as Address,
+            size: u32_at(entry, 8) as usize,
+            offset: u32_at(entry, 12) as u64,
+        })
+        .collect())
+}
+
+fn read_memory64_list(stream: &[u8]) -> io::Result<Vec<MemoryRange>> {
+    if stream.len() < 16 {
+        return Err(invalid_data("truncated minidump stream"));
+    }
+    let count = u64_at(stream, 0) as usize;
+    let mut offset = u64_at(stream, 8);
+    let entries = count
+        .checked_mul(MEMORY_DESCRIPTOR_SIZE)
+        .and_then(|len| stream.get(16..16 + len))
+        .ok_or_else(|| invalid_data("truncated memory64 list"))?;
+
+    Ok(entries
+        .chunks_exact(MEMORY_DESCRIPTOR_SIZE)
+        .map(|entry| {
+            let range = MemoryRange {
+                base: u64_at(entry, 0) as Address,
+                size: u64_at(entry, 8) as usize,
+                offset,
+            };
+            offset += range.size as u64;
+            range
+        })
+        .collect())
+}
+
+fn read_memory_info_list(stream: &[u8]) -> io::Result<Vec<MemoryRegion>> {
+    if stream.len() < 16 {
+        return Err(invalid_data("truncated minidump stream"));
+    }
+    let header_size = u32_at(stream, 0) as usize;
+    let entry_size = u32_at(stream, 4) as usize;
+    let count = u64_at(stream, 8) as usize;
+    if entry_size < MEMORY_INFO_SIZE {
+        return Err(invalid_data("invalid memory info list"));
+    }
+    let entries = count
+        .checked_mul(entry_size)
+        .and_then(|len| stream.get(header_size..header_size.checked_add(len)?))
+        .ok_or_else(|| invalid_data("truncated memory info list"))?;
+
+    Ok(entries
+        .chunks_exact(entry_size)
+        .map(|entry| MemoryRegion {
+            base: u64_at(entry, 0) as Address,
+            size: u64_at(entry, 24) as usize,
+            state: RegionState::from_windows(u32_at(entry, 32)),
+            protection: Protection::from_windows(u32_at(entry, 36)),
+            kind: RegionType::from_windows(u32_at(entry, 40)),
+        })
+        .collect())
+}
+
+fn read_string(file: &mut File, rva: u64) -> io::Result<String> {
+    let len = u32_at(&read_at(file, rva, 4)?, 0) as usize;
+    let bytes = read_at(file, rva + 4, len)?;
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+        .collect();
+    Ok(String::from_utf16_lossy(&units))
+}
+
+fn read_at(file: &mut File, offset: u64, len: usize) -> io::Result<Vec<u8>> {
+    if len > MAX_STREAM_SIZE {
+        return Err(invalid_data("minidump stream too large"));
+    }
+    let mut bytes = vec![0u8; len];
+    file.seek(SeekFrom::Start(offset))?;
+    file.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+fn u64_at(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODULE_BASE: u64 = 0x1_4000_0000;
+    const MODULE_PATH: &str = "C:\\Games\\Minecraft.Windows.exe";
+    const MEM_COMMIT: u32 = 0x1000;
+    const MEM_IMAGE: u32 = 0x100_0000;
+    const PAGE_READONLY: u32 = 0x02;
+
+    fn fixture(with_info: bool) -> Vec<u8> {
+        let mut path = Vec::new();
+        for unit in MODULE_PATH.encode_utf16() {
+            path.extend(unit.to_le_bytes());
+        }
+
+        let mut modules = 1u32.to_le_bytes().to_vec();
+        let mut module = [0u8; MODULE_SIZE];
+        module[..8].copy_from_slice(&MODULE_BASE.to_le_bytes());
+        module[8..12].copy_from_slice(&0x20u32.to_le_bytes());
+        modules.extend(module);
+
+        let mut memory = Vec::new();
+        for (base, size) in [(MODULE_BASE, 0x10u64), (MODULE_BASE + 0x10, 0x10)] {
+            memory.extend(base.to_le_bytes());
+            memory.extend(size.to_le_bytes());
+        }
+        let mut info = Vec::new();
+        for value in [16u32, MEMORY_INFO_SIZE as u32] {
+            info.extend(value.to_le_bytes());
+        }
+        info.extend(1u64.to_le_bytes());
+        let mut region = [0u8; MEMORY_INFO_SIZE];
+        region[..8].copy_from_slice(&MODULE_BASE.to_le_bytes());
+        region[24..32].copy_from_slice(&0x20u64.to_le_bytes());
+        region[32..36].copy_from_slice(&MEM_COMMIT.to_le_bytes());
+        region[36..40].copy_from_slice(&PAGE_READONLY.to_le_bytes());
+        region[40..44].copy_from_slice(&MEM_IMAGE.to_le_bytes());
+        info.extend(region);
+
+        let stream_count = if with_info { 3 } else { 2 };
+        let modules_rva = HEADER_SIZE + stream_count * DIRECTORY_ENTRY_SIZE;
+        let path_rva = modules_rva + modules.len();
+        let memory_rva = path_rva + 4 + path.len();
+        let info_rva = memory_rva + 16 + memory.len();
+        let data_rva = info_rva + if with_info { info.len() } else { 0 };
+        modules[4 + 20..4 + 24].copy_from_slice(&(path_rva as u32).to_le_bytes());
+
+        let mut file = SIGNATURE.to_vec();
+        for value in [0xA793u32, stream_count as u32, HEADER_SIZE as u32] {
+            file.extend(value.to_le_bytes());
+        }
+        file.resize(HEADER_SIZE, 0);
+        let mut streams = vec![
+            (MODULE_LIST_STREAM, modules.len(), modules_rva),
+            (MEMORY64_LIST_STREAM, 16 + memory.len(), memory_rva),
+        ];
+        if with_info {
+            streams.push((MEMORY_INFO_LIST_STREAM, info.len(), info_rva));
+        }
+        for (kind, size, rva) in streams {
+            for value in [kind, size as u32, rva as u32] {
+                file.extend(value.to_le_bytes());
+            }
+        }
+
+        file.extend(modules);
+        file.extend((path.len() as u32).to_le_bytes());
+        file.extend(path);
+        file.extend(2u64.to_le_bytes());
+        file.extend((data_rva as u64).to_le_bytes());
+        file.extend(memory);
+        if with_info {
+            file.extend(info);
+        }
+        file.extend(0u8..0x20);
+        file
+    }
+
+    fn open(name: &str, bytes: &[u8]) -> io::Result<Minidump> {
+        let path = std::env::temp_dir().join(format!(
+            "mcbe-fisher-minidump-{}-{}.dmp",
+            name,
+            std::process::id()
+        ));
+        std::fs::write(&path, bytes).unwrap();
+        let dump = Minidump::open(&path);
+        let _ = std::fs::remove_file(&path);
+        dump
+    }
+
+    #[test]
+    fn reads_memory_across_ranges() {
+        let dump = open("memory", &fixture(true)).unwrap();
+        let base = MODULE_BASE as Address;
+        assert_eq!(dump.read_bytes(base + 2, 3).unwrap(), vec![2, 3, 4]);
+        assert_eq!(
+            dump.read_bytes(base + 0xE, 4).unwrap(),
+            vec![0xE, 0xF, 0x10, 0x11]
+        );
+        assert!(matches!(
+            dump.read_bytes(base + 0x1E, 4),
+            Err(MemoryError::PartialCopy(_))
+        ));
+        assert!(matches!(
+            dump.read_bytes(base - 1, 1),
+            Err(MemoryError::InvalidAddress(_))
+        ));
+    }
+
+    #[test]
+    fn reads_modules() {
+        let dump = open("modules", &fixture(true)).unwrap();
+        let modules = dump.modules().unwrap();
+        assert_eq!(modules.len(), 1);
+        assert_eq!(modules[0].name, "Minecraft.Windows.exe");
+        assert_eq!(modules[0].path, PathBuf::from(MODULE_PATH));
+        assert_eq!(
+            (modules[0].base, modules[0].size),
+            (MODULE_BASE as Address, 0x20)
+        );
+    }
+
+    #[test]
+    fn reads_regions() {
+        let dump = open("regions", &fixture(true)).unwrap();
+        let regions = dump.regions().unwrap();
+        assert_eq!(regions.len(), 1);
+        assert_eq!(regions[0].size, 0x20);
+        assert_eq!(regions[0].state, RegionState::Committed);
+        assert_eq!(regions[0].kind, RegionType::Image);
+        assert!(regions[0].is_readable() && !regions[0].protection.write);
+
+        let dump = open("ranges", &fixture(false)).unwrap();
+        let bases: Vec<Address> = dump
+            .regions()
+            .unwrap()
+            .iter()
+            .map(|region| region.base)
+            .collect();
+        let base = MODULE_BASE as Address;
+        assert_eq!(bases, vec![base, base + 0x10]);
+    }
+
+    #[test]
+    fn rejects_broken_dumps() {
+        assert!(open("signature", b"MDMX").is_err());
+        let mut truncated = fixture(true);
+        truncated.truncate(HEADER_SIZE + 10);
+        assert!(open("truncated", &truncated).is_err());
+    }
+}
@@ -0,0 +1,269 @@
+use bytemuck::Pod;
+use std::ops::Range;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+mod cache;
+mod chain;
+mod error;
+#[cfg(test)]
+mod fake;
+#[cfg(target_os = "linux")]
+mod linux;
+mod minidump;
+mod pod;
+mod region;
+mod snapshot;
+#[cfg(windows)]
+mod windows;
+
+pub use cache::PageCache;
+pub use chain::{PointerHop, PointerTrace};
+pub use error::MemoryError;
+#[cfg(test)]
+pub use fake::FakeMemory;
+#[cfg(target_os = "linux")]
+pub use linux::{find_processes, MemoryReader};
+pub use minidump::Minidump;
+pub use pod::{LittleEndian, Vec3};
+pub use region::{MemoryRegion, Protection, RegionState, RegionType};
+pub use snapshot::Snapshot;
+#[cfg(windows)]
+pub use windows::{find_processes, MemoryReader};
+
+#[cfg(windows)]
+pub const DEFAULT_PROCESS: &str = "Minecraft.Windows.exe";
+#[cfg(windows)]
+pub const DEFAULT_MODULE: &str = "Minecraft.Windows.exe";
+
+#[cfg(not(windows))]
+pub const DEFAULT_PROCESS: &str = "mcpelauncher-client";
+#[cfg(not(windows))]
+pub const DEFAULT_MODULE: &str = "libminecraftpe.so";
+
+const CHUNK_SIZE: usize = 0x10_0000;
+const PAGE_SIZE: usize = 0x1000;
+
+pub type Address = usize;
+pub type Result<T> = std::result::Result<T, MemoryError>;
+
+#[derive(Clone, Debug)]
+pub struct ModuleInfo {
+    pub name: String,
+    pub base: Address,
+    pub size: usize,
+    pub path: PathBuf,
+}
+
+#[derive(Clone, Debug)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub name: String,
+    pub path: Option<PathBuf>,
+    pub start_time: Option<SystemTime>,
+    pub window_title: Option<String>,
+}
+
+pub fn get_pid(process_name: &str) -> Option<u32> {
+    find_processes(process_name)
+        .first()
+        .map(|process| process.pid)
+}
+
+pub fn read_chunks<M: ProcessMemory>(
+    memory: &M,
+    range: Range<Address>,
+    mut callback: impl FnMut(Address, &[u8]),
+) {
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    let mut address = range.start;
+
+    while address < range.end {
+        let chunk = &mut buffer[..CHUNK_SIZE.min(range.end - address)];
+        if memory.read_raw(address, chunk).is_ok() {
+            callback(address, chunk);
+        } else {
+            for (index, page) in chunk.chunks_mut(PAGE_SIZE).enumerate() {
+                let page_address = address + index * PAGE_SIZE;
+                if memory.read_raw(page_address, page).is_ok() {
+                    callback(page_address, page);
+                }
+            }
+        }
+        address += chunk.len();
+    }
+}
+
+pub trait ProcessMemory: Send + Sync {
+    fn read_raw(&self, address: Address, buffer: &mut [u8]) -> Result<()>;
+
+    fn modules(&self) -> Result<Vec<ModuleInfo>>;
+
+    fn regions(&self) -> Result<Vec<MemoryRegion>>;
+
+    fn region_at(&self, address: Address) -> Result<Option<MemoryRegion>> {
+        Ok(self
+            .regions()?
+            .into_iter()
+            .find(|region| region.contains(address)))
+    }
+
+    fn is_alive(&self) -> bool {
+        true
+    }
+
+    fn readable_ranges(&self) -> Result<Vec<Range<Address>>> {
+        let mut ranges: Vec<Range<Address>> = Vec::new();
+        for region in self.regions()? {
+            if !region.is_readable() {
+                continue;
+            }
+            match ranges.last_mut() {
+                Some(last) if last.end == region.base => last.end = region.end(),
+                _ => ranges.push(region.base..region.end()),
+            }
+        }
+        Ok(ranges)
+    }
+
+    fn check_readable(&self, address: Address) -> Result<()> {
+        match self.region_at(address) {
+            Ok(Some(region)) if region.is_readable() => Ok(()),
+            Ok(Some(region)) if region.state == RegionState::Committed => {
+                Err(MemoryError::Protected(address))
+            }
+            Ok(_) => Err(MemoryError::InvalidAddress(address)),
+            Err(e @ (MemoryError::ProcessGone | MemoryError::Io(_))) => Err(e),
+            Err(_) => Ok(()),
+        }
+    }
+
+    fn find_module(&self, module_name: &str) -> Result<ModuleInfo> {
+        self.modules()?
+            .into_iter()
+            .find(|module| module.name.eq_ignore_ascii_case(module_name))
+            .ok_or_else(|| MemoryError::NotFound(format!("module {}", module_name)))
+    }
+
+    fn read_bytes(&self, address: Address, len: usize) -> Result<Vec<u8>> {
+        let mut buffer = vec![0u8; len];
+        self.read_raw(address, &mut buffer)?;
+        Ok(buffer)
+    }
+
+    fn read_slice<T: Pod>(&self, address: Address, count: usize) -> Result<Vec<T>> {
+        let mut values = vec![T::zeroed(); count];
+        self.read_raw(address, bytemuck::cast_slice_mut(&mut values))?;
+        Ok(values)
+    }
+
+    fn read<T: Pod>(&self, address: Address) -> Result<T> {
+        let mut value = T::zeroed();
+        self.read_raw(address, bytemuck::bytes_of_mut(&mut value))?;
+        Ok(value)
+    }
+
+    fn read_le<T: LittleEndian>(&self, address: Address) -> Result<T> {
+        self.read::<T>(address).map(T::to_native)
+    }
+
+    fn follow_pointers(&self, base: Address, offsets: &[usize]) -> Result<Address> {
+        let trace = self.trace_pointers(base, offsets);
+        match trace.error {
+            Some(e) => Err(e),
+            None => Ok(trace.address().unwrap_or(base)),
+        }
+    }
+
+    fn trace_pointers(&self, base: Address, offsets: &[usize]) -> PointerTrace {
+        let mut trace = PointerTrace {
+            base,
+            hops: Vec::with_capacity(offsets.len()),
+            error: None,
+        };
+
+        let mut addr = base;
+        for &offset in offsets {
+            let value = self
+                .check_readable(addr)
+                .and_then(|_| self.read_le::<usize>(addr));
+            trace.hops.push(PointerHop {
+                address: addr,
+                value: value.as_ref().ok().copied(),
+                offset,
+            });
+
+            match value {
+                Ok(value) => addr = value.wrapping_add(offset),
+                Err(e) => {
+                    trace.error = Some(e);
+                    break;
+                }
+            }
+        }
+        trace
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODULE_BASE: Address = 0x1_4000_0000;
+
+    fn chain() -> FakeMemory {
+        let mut memory = FakeMemory::new();
+        memory.add_module("Minecraft.Windows.exe", MODULE_BASE, 0x1000);
+        memory.put(MODULE_BASE + 0x100, 0x2000_0000usize);
+        memory.put(0x2000_0010, 0x3000_0000usize);
+        memory.put(0x3000_000C, 7u32);
+        memory
+    }
+
+    #[test]
+    fn follows_pointers() {
+        let memory = chain();
+        let address = memory
+            .follow_pointers(MODULE_BASE + 0x100, &[0x10, 0xC])
+            .unwrap();
+        assert_eq!(address, 0x3000_000C);
+        assert_eq!(memory.read_le::<u32>(address).unwrap(), 7);
+    }
+
+    #[test]
+    fn traces_every_hop() {
+        let memory = chain();
+        let trace = memory.trace_pointers(MODULE_BASE + 0x100, &[0x10, 0xC]);
+        assert!(trace.error.is_none());
+        assert_eq!(
+            trace.addresses(),
+            vec![MODULE_BASE + 0x100, 0x2000_0010, 0x3000_000C]
+        );
+        assert_eq!(trace.hops[1].value, Some(0x3000_0000));
+    }
+
+    #[test]
+    fn reports_the_broken_hop() {
+        let memory = chain();
+        memory.put(0x2000_0010, 0x5000_0000usize);
+        let trace = memory.trace_pointers(MODULE_BASE + 0x100, &[0x10, 0xC, 0x8]);
+        assert_eq!(trace.failed_at(), Some(2));
+        assert!(matches!(
+            trace.error,
+            Some(MemoryError::InvalidAddress(0x5000_000C))
+        ));
+        assert!(memory
+            .follow_pointers(MODULE_BASE + 0x100, &[0x10, 0xC, 0x8])
+            .is_err());
+    }
+
+    #[test]
+    fn finds_modules_by_name() {
+        let memory = chain();
+        assert_eq!(
+            memory.find_module("minecraft.windows.exe").unwrap().base,
+            MODULE_BASE
+        );
+        assert!(memory.find_module("missing.dll").is_err());
+    }
+}
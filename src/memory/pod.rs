@@ -0,0 +1,49 @@
+use bytemuck::{Pod, Zeroable};
+
+pub trait LittleEndian: Pod {
+    fn to_native(self) -> Self;
+}
+
+macro_rules! little_endian_int {
+    ($($t:ty),*) => {
+        $(
+            impl LittleEndian for $t {
+                fn to_native(self) -> Self {
+                    <$t>::from_le(self)
+                }
+            }
+        )*
+    };
+}
+
+little_endian_int!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+impl LittleEndian for f32 {
+    fn to_native(self) -> Self {
+        f32::from_bits(u32::from_le(self.to_bits()))
+    }
+}
+
+impl LittleEndian for f64 {
+    fn to_native(self) -> Self {
+        f64::from_bits(u64::from_le(self.to_bits()))
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct Vec3 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+impl LittleEndian for Vec3 {
+    fn to_native(self) -> Self {
+        Vec3 {
+            x: self.x.to_native(),
+            y: self.y.to_native(),
+            z: self.z.to_native(),
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use super::Address;
+use std::fmt;
+
+const MEM_COMMIT: u32 = 0x1000;
+const MEM_RESERVE: u32 = 0x2000;
+const MEM_PRIVATE: u32 = 0x20000;
+const MEM_MAPPED: u32 = 0x40000;
+const MEM_IMAGE: u32 = 0x1000000;
+
+const PAGE_READONLY: u32 = 0x02;
+const PAGE_READWRITE: u32 = 0x04;
+const PAGE_WRITECOPY: u32 = 0x08;
+const PAGE_EXECUTE: u32 = 0x10;
+const PAGE_EXECUTE_READ: u32 = 0x20;
+const PAGE_EXECUTE_READWRITE: u32 = 0x40;
+const PAGE_EXECUTE_WRITECOPY: u32 = 0x80;
+const PAGE_GUARD: u32 = 0x100;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RegionState {
+    Committed,
+    Reserved,
+    Free,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RegionType {
+    Image,
+    Mapped,
+    Private,
+    Unknown,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Protection {
+    pub read: bool,
+    pub write: bool,
+    pub execute: bool,
+    pub guard: bool,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct MemoryRegion {
+    pub base: Address,
+    pub size: usize,
+    pub state: RegionState,
+    pub protection: Protection,
+    pub kind: RegionType,
+}
+
+impl RegionState {
+    pub fn from_windows(state: u32) -> Self {
+        match state {
+            MEM_COMMIT => RegionState::Committed,
+            MEM_RESERVE => RegionState::Reserved,
+            _ => RegionState::Free,
+        }
+    }
+}
+
+impl RegionType {
+    pub fn from_windows(kind: u32) -> Self {
+        match kind {
+            MEM_IMAGE => RegionType::Image,
+            MEM_MAPPED => RegionType::Mapped,
+            MEM_PRIVATE => RegionType::Private,
+            _ => RegionType::Unknown,
+        }
+    }
+}
+
+impl Protection {
+    pub const READ_ONLY: Protection = Protection {
+        read: true,
+        write: false,
+        execute: false,
+        guard: false,
+    };
+
+    pub fn from_windows(protect: u32) -> Self {
+        let (read, write, execute) = match protect & 0xFF {
+            PAGE_READONLY => (true, false, false),
+            PAGE_READWRITE | PAGE_WRITECOPY => (true, true, false),
+            PAGE_EXECUTE => (false, false, true),
+            PAGE_EXECUTE_READ => (true, false, true),
+            PAGE_EXECUTE_READWRITE | PAGE_EXECUTE_WRITECOPY => (true, true, true),
+            _ => (false, false, false),
+        };
+        Protection {
+            read,
+            write,
+            execute,
+            guard: protect & PAGE_GUARD != 0,
+        }
+    }
+
+    pub fn from_unix(perms: &str) -> Self {
+        let perms = perms.as_bytes();
+        Protection {
+            read: perms.first() == Some(&b'r'),
+            write: perms.get(1) == Some(&b'w'),
+            execute: perms.get(2) == Some(&b'x'),
+            guard: false,
+        }
+    }
+}
+
+impl MemoryRegion {
+    pub fn end(&self) -> Address {
+        self.base + self.size
+    }
+
+    pub fn contains(&self, address: Address) -> bool {
+        (self.base..self.end()).contains(&address)
+    }
+
+    pub fn is_readable(&self) -> bool {
+        self.state == RegionState::Committed && self.protection.read && !self.protection.guard
+    }
+}
+
+impl fmt::Display for Protection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let flag = |set: bool, c: char| if set { c } else { '-' };
+        write!(
+            f,
+            "{}{}{}{}",
+            flag(self.read, 'r'),
+            flag(self.write, 'w'),
+            flag(self.execute, 'x'),
+            flag(self.guard, 'g')
+        )
+    }
+}
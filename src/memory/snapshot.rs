@@ -0,0 +1,243 @@
+use super::{
+    Address, MemoryError, MemoryRegion, ModuleInfo, ProcessMemory, Protection, RegionState,
+    RegionType, Result,
+};
+use crate::binary;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::ops::Range;
+use std::path::Path;
+
+const MAGIC: &[u8; 4] = b"MCSS";
+const VERSION: u32 = 1;
+const PAGE_SIZE: usize = 0x1000;
+
+pub struct Snapshot {
+    modules: Vec<ModuleInfo>,
+    regions: Vec<(Address, Vec<u8>)>,
+}
+
+impl Snapshot {
+    pub fn capture<M: ProcessMemory>(
+        memory: &M,
+        addresses: &[Address],
+        radius: usize,
+    ) -> Result<Self> {
+        let modules = memory.modules()?;
+        let mut ranges: Vec<Range<Address>> = modules
+            .iter()
+            .map(|module| module.base..module.base + PAGE_SIZE)
+            .collect();
+        for &address in addresses {
+            let start = address.saturating_sub(radius) & !(PAGE_SIZE - 1);
+            let end = (address.saturating_add(radius) | (PAGE_SIZE - 1)).saturating_add(1);
+            ranges.push(start..end);
+        }
+
+        Ok(Self::read_ranges(memory, modules, merge_ranges(ranges)))
+    }
+
+    pub fn capture_full<M: ProcessMemory>(memory: &M) -> Result<Self> {
+        let modules = memory.modules()?;
+        let ranges = memory.readable_ranges()?;
+        Ok(Self::read_ranges(memory, modules, ranges))
+    }
+
+    fn read_ranges<M: ProcessMemory>(
+        memory: &M,
+        modules: Vec<ModuleInfo>,
+        ranges: Vec<Range<Address>>,
+    ) -> Self {
+        let mut regions: Vec<(Address, Vec<u8>)> = Vec::new();
+        for range in ranges {
+            super::read_chunks(memory, range, |address, bytes| match regions.last_mut() {
+                Some((base, data)) if *base + data.len() == address => {
+                    data.extend_from_slice(bytes)
+                }
+                _ => regions.push((address, bytes.to_vec())),
+            });
+        }
+        Snapshot { modules, regions }
+    }
+
+    pub fn size(&self) -> usize {
+        self.regions.iter().map(|(_, data)| data.len()).sum()
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        binary::write_header(&mut writer, MAGIC, VERSION)?;
+        binary::write_modules(&mut writer, &self.modules)?;
+
+        binary::write_u64(&mut writer, self.regions.len() as u64)?;
+        for (base, data) in &self.regions {
+            binary::write_u64(&mut writer, *base as u64)?;
+            binary::write_u64(&mut writer, data.len() as u64)?;
+            writer.write_all(data)?;
+        }
+        writer.flush()
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+        binary::read_header(&mut reader, MAGIC, VERSION, "memory snapshot")?;
+        let modules = binary::read_modules(&mut reader)?;
+
+        let count = binary::read_u64(&mut reader)?;
+        let mut regions: Vec<(Address, Vec<u8>)> = Vec::new();
+        for _ in 0..count {
+            let base = binary::read_u64(&mut reader)? as Address;
+            let len = binary::read_u64(&mut reader)?;
+            let mut data = Vec::new();
+            reader.by_ref().take(len).read_to_end(&mut data)?;
+            if data.len() as u64 != len {
+                return Err(binary::invalid_data("truncated memory snapshot"));
+            }
+            if base.checked_add(data.len()).is_none() {
+                return Err(binary::invalid_data(
+                    "snapshot region past the end of memory",
+                ));
+            }
+            if regions
+                .last()
+                .is_some_and(|(last, last_data)| last + last_data.len() > base)
+            {
+                return Err(binary::invalid_data("overlapping snapshot regions"));
+            }
+            regions.push((base, data));
+        }
+
+        Ok(Snapshot { modules, regions })
+    }
+}
+
+impl ProcessMemory for Snapshot {
+    fn read_raw(&self, address: Address, buffer: &mut [u8]) -> Result<()> {
+        let index = self.regions.partition_point(|(base, _)| *base <= address);
+        let (base, data) = match index.checked_sub(1).map(|index| &self.regions[index]) {
+            Some(region) => region,
+            None => return Err(MemoryError::InvalidAddress(address)),
+        };
+
+        let offset = address - base;
+        if offset >= data.len() {
+            return Err(MemoryError::InvalidAddress(address));
+        }
+        if offset + buffer.len() > data.len() {
+            return Err(MemoryError::PartialCopy(address));
+        }
+        buffer.copy_from_slice(&data[offset..offset + buffer.len()]);
+        Ok(())
+    }
+
+    fn modules(&self) -> Result<Vec<ModuleInfo>> {
+        Ok(self.modules.clone())
+    }
+
+    fn regions(&self) -> Result<Vec<MemoryRegion>> {
+        Ok(self
+            .regions
+            .iter()
+            .map(|(base, data)| MemoryRegion {
+                base: *base,
+                size: data.len(),
+                state: RegionState::Committed,
+                protection: Protection::READ_ONLY,
+                kind: RegionType::Unknown,
+            })
+            .collect())
+    }
+}
+
+fn merge_ranges(mut ranges: Vec<Range<Address>>) -> Vec<Range<Address>> {
+    ranges.sort_by_key(|range| range.start);
+    let mut merged: Vec<Range<Address>> = Vec::new();
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if last.end >= range.start => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::FakeMemory;
+
+    const MODULE_BASE: Address = 0x1_4000_0000;
+    const HEAP: Address = 0x2000_0000;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!(
+            "mcbe-fisher-snapshot-{}-{}.bin",
+            name,
+            std::process::id()
+        ))
+    }
+
+    fn load(name: &str, regions: &[(u64, u64, usize)]) -> io::Result<Snapshot> {
+        let path = temp_path(name);
+        let mut file = Vec::new();
+        binary::write_header(&mut file, MAGIC, VERSION).unwrap();
+        binary::write_modules(&mut file, &[]).unwrap();
+        binary::write_u64(&mut file, regions.len() as u64).unwrap();
+        for &(base, len, written) in regions {
+            binary::write_u64(&mut file, base).unwrap();
+            binary::write_u64(&mut file, len).unwrap();
+            file.extend(std::iter::repeat_n(0xAA, written));
+        }
+        std::fs::write(&path, file).unwrap();
+        let snapshot = Snapshot::load(&path);
+        let _ = std::fs::remove_file(&path);
+        snapshot
+    }
+
+    #[test]
+    fn round_trips_a_capture() {
+        let mut memory = FakeMemory::new();
+        memory.add_module("Minecraft.Windows.exe", MODULE_BASE, 0x2000);
+        memory.put(MODULE_BASE + 0x100, HEAP + 0x800);
+        memory.write(HEAP, &[0u8; PAGE_SIZE]);
+        memory.put(HEAP + 0x800, 7u32);
+
+        let snapshot = Snapshot::capture(&memory, &[HEAP + 0x800], 0x10).unwrap();
+        assert_eq!(snapshot.size(), 2 * PAGE_SIZE);
+
+        let path = temp_path("round-trip");
+        snapshot.save(&path).unwrap();
+        let loaded = Snapshot::load(&path);
+        let _ = std::fs::remove_file(&path);
+
+        let loaded = loaded.unwrap();
+        assert_eq!(loaded.regions().unwrap().len(), 2);
+        assert_eq!(
+            loaded.find_module("minecraft.windows.exe").unwrap().base,
+            MODULE_BASE
+        );
+        assert_eq!(
+            loaded.follow_pointers(MODULE_BASE + 0x100, &[0]).unwrap(),
+            HEAP + 0x800
+        );
+        assert_eq!(loaded.read_le::<u32>(HEAP + 0x800).unwrap(), 7);
+        assert!(matches!(
+            loaded.read_le::<u32>(MODULE_BASE + 0x1000),
+            Err(MemoryError::InvalidAddress(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_broken_files() {
+        assert!(load("valid", &[(0x1000, 0x20, 0x20), (0x1020, 0x10, 0x10)]).is_ok());
+
+        let truncated = load("truncated", &[(0x1000, 0x20, 4)]).err().unwrap();
+        assert_eq!(truncated.kind(), io::ErrorKind::InvalidData);
+
+        let overlapping = load("overlapping", &[(0x1000, 0x20, 0x20), (0x1010, 0x10, 0x10)]);
+        assert!(overlapping.is_err());
+
+        let wrapping = load("wrapping", &[(u64::MAX - 4, 0x10, 0x10)]);
+        assert!(wrapping.is_err());
+    }
+}
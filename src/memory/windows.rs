@@ -0,0 +1,320 @@
+use super::{
+    Address, MemoryError, MemoryRegion, ModuleInfo, ProcessInfo, ProcessMemory, Protection,
+    RegionState, RegionType, Result,
+};
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::io::Error;
+use std::mem::size_of;
+use std::os::windows::ffi::OsStringExt;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use winapi::shared::minwindef::{BOOL, FILETIME, HMODULE, LPARAM, TRUE};
+use winapi::shared::windef::HWND;
+use winapi::shared::winerror::{
+    ERROR_ACCESS_DENIED, ERROR_INVALID_ADDRESS, ERROR_INVALID_HANDLE, ERROR_INVALID_PARAMETER,
+    ERROR_NOACCESS, ERROR_PARTIAL_COPY,
+};
+use winapi::um::handleapi::CloseHandle;
+use winapi::um::memoryapi::{ReadProcessMemory, VirtualQueryEx};
+use winapi::um::minwinbase::STILL_ACTIVE;
+use winapi::um::processthreadsapi::{GetExitCodeProcess, GetProcessTimes, OpenProcess};
+use winapi::um::psapi::{
+    EnumProcessModulesEx, EnumProcesses, GetModuleBaseNameW, GetModuleFileNameExW,
+    GetModuleInformation, LIST_MODULES_ALL, MODULEINFO,
+};
+use winapi::um::winbase::QueryFullProcessImageNameW;
+use winapi::um::winnt::{
+    HANDLE, MEMORY_BASIC_INFORMATION, PROCESS_QUERY_INFORMATION, PROCESS_QUERY_LIMITED_INFORMATION,
+    PROCESS_VM_READ,
+};
+use winapi::um::winuser::{EnumWindows, GetWindowTextW, GetWindowThreadProcessId, IsWindowVisible};
+
+pub struct MemoryReader {
+    handle: HANDLE,
+}
+
+unsafe impl Send for MemoryReader {}
+unsafe impl Sync for MemoryReader {}
+
+impl MemoryReader {
+    pub fn new(pid: u32) -> Result<Self> {
+        let handle = unsafe { OpenProcess(PROCESS_VM_READ | PROCESS_QUERY_INFORMATION, 0, pid) };
+        if handle.is_null() {
+            return Err(match last_error_code() {
+                ERROR_ACCESS_DENIED => MemoryError::AccessDenied,
+                ERROR_INVALID_PARAMETER => MemoryError::ProcessGone,
+                _ => MemoryError::Io(Error::last_os_error()),
+            });
+        }
+
+        Ok(MemoryReader { handle })
+    }
+
+    fn last_error(&self, address: Address, bytes_read: usize) -> MemoryError {
+        let error = Error::last_os_error();
+        if !self.is_alive() {
+            return MemoryError::ProcessGone;
+        }
+
+        match error.raw_os_error().unwrap_or_default() as u32 {
+            ERROR_ACCESS_DENIED => MemoryError::AccessDenied,
+            ERROR_INVALID_HANDLE => MemoryError::ProcessGone,
+            ERROR_PARTIAL_COPY if bytes_read > 0 => MemoryError::PartialCopy(address),
+            ERROR_PARTIAL_COPY | ERROR_INVALID_ADDRESS | ERROR_NOACCESS => {
+                MemoryError::InvalidAddress(address)
+            }
+            _ => MemoryError::Io(error),
+        }
+    }
+}
+
+impl ProcessMemory for MemoryReader {
+    fn read_raw(&self, address: Address, buffer: &mut [u8]) -> Result<()> {
+        let mut bytes_read = 0;
+
+        let success = unsafe {
+            ReadProcessMemory(
+                self.handle,
+                address as *const winapi::ctypes::c_void,
+                buffer.as_mut_ptr() as *mut winapi::ctypes::c_void,
+                buffer.len(),
+                &mut bytes_read,
+            ) != 0
+        };
+
+        if success {
+            Ok(())
+        } else {
+            Err(self.last_error(address, bytes_read))
+        }
+    }
+
+    fn modules(&self) -> Result<Vec<ModuleInfo>> {
+        let mut modules: Vec<HMODULE> = vec![std::ptr::null_mut(); 256];
+
+        loop {
+            let mut needed = 0;
+            let success = unsafe {
+                EnumProcessModulesEx(
+                    self.handle,
+                    modules.as_mut_ptr(),
+                    (modules.len() * size_of::<HMODULE>()) as u32,
+                    &mut needed,
+                    LIST_MODULES_ALL,
+                ) != 0
+            };
+            if !success {
+                let error = Error::last_os_error();
+                return Err(if self.is_alive() {
+                    MemoryError::from(error)
+                } else {
+                    MemoryError::ProcessGone
+                });
+            }
+
+            let count = needed as usize / size_of::<HMODULE>();
+            if count <= modules.len() {
+                modules.truncate(count);
+                break;
+            }
+            modules.resize(count, std::ptr::null_mut());
+        }
+
+        let modules: Vec<ModuleInfo> = modules
+            .into_iter()
+            .filter_map(|module| module_info(self.handle, module).ok())
+            .collect();
+        if modules.is_empty() && !self.is_alive() {
+            return Err(MemoryError::ProcessGone);
+        }
+        Ok(modules)
+    }
+
+    fn regions(&self) -> Result<Vec<MemoryRegion>> {
+        let mut regions = Vec::new();
+        let mut address: Address = 0;
+
+        while let Some(region) = query_region(self.handle, address) {
+            let end = region.end();
+            regions.push(region);
+            if end <= address {
+                break;
+            }
+            address = end;
+        }
+
+        if regions.is_empty() && !self.is_alive() {
+            return Err(MemoryError::ProcessGone);
+        }
+        Ok(regions)
+    }
+
+    fn region_at(&self, address: Address) -> Result<Option<MemoryRegion>> {
+        match query_region(self.handle, address) {
+            Some(region) => Ok(Some(region)),
+            None if self.is_alive() => Ok(None),
+            None => Err(MemoryError::ProcessGone),
+        }
+    }
+
+    fn is_alive(&self) -> bool {
+        let mut exit_code = 0;
+        unsafe { GetExitCodeProcess(self.handle, &mut exit_code) != 0 && exit_code == STILL_ACTIVE }
+    }
+}
+
+fn query_region(handle: HANDLE, address: Address) -> Option<MemoryRegion> {
+    let mut info: MEMORY_BASIC_INFORMATION = unsafe { std::mem::zeroed() };
+    let written = unsafe {
+        VirtualQueryEx(
+            handle,
+            address as *const winapi::ctypes::c_void,
+            &mut info,
+            size_of::<MEMORY_BASIC_INFORMATION>(),
+        )
+    };
+    if written == 0 {
+        return None;
+    }
+
+    Some(MemoryRegion {
+        base: info.BaseAddress as Address,
+        size: info.RegionSize,
+        state: RegionState::from_windows(info.State),
+        protection: Protection::from_windows(info.Protect),
+        kind: RegionType::from_windows(info.Type),
+    })
+}
+
+fn last_error_code() -> u32 {
+    Error::last_os_error().raw_os_error().unwrap_or_default() as u32
+}
+
+fn module_info(handle: HANDLE, module: HMODULE) -> Result<ModuleInfo> {
+    let mut info: MODULEINFO = unsafe { std::mem::zeroed() };
+    let mut name = [0u16; 260];
+    let mut path = [0u16; 1024];
+
+    let (name_len, path_len) = unsafe {
+        if GetModuleInformation(handle, module, &mut info, size_of::<MODULEINFO>() as u32) == 0 {
+            return Err(MemoryError::Io(Error::last_os_error()));
+        }
+
+        let name_len = GetModuleBaseNameW(handle, module, name.as_mut_ptr(), name.len() as u32);
+        if name_len == 0 {
+            return Err(MemoryError::Io(Error::last_os_error()));
+        }
+
+        let path_len = GetModuleFileNameExW(handle, module, path.as_mut_ptr(), path.len() as u32);
+        (name_len as usize, path_len as usize)
+    };
+
+    Ok(ModuleInfo {
+        name: String::from_utf16_lossy(&name[..name_len]),
+        base: info.lpBaseOfDll as Address,
+        size: info.SizeOfImage as usize,
+        path: PathBuf::from(OsString::from_wide(&path[..path_len])),
+    })
+}
+
+impl Drop for MemoryReader {
+    fn drop(&mut self) {
+        unsafe { CloseHandle(self.handle) };
+    }
+}
+
+pub fn find_processes(process_name: &str) -> Vec<ProcessInfo> {
+    let mut pids = vec![0u32; 1024];
+
+    loop {
+        let mut bytes_returned = 0;
+        let success = unsafe {
+            EnumProcesses(
+                pids.as_mut_ptr(),
+                (pids.len() * size_of::<u32>()) as u32,
+                &mut bytes_returned,
+            ) != 0
+        };
+        if !success {
+            return Vec::new();
+        }
+
+        let count = bytes_returned as usize / size_of::<u32>();
+        if count < pids.len() {
+            pids.truncate(count);
+            break;
+        }
+        pids.resize(pids.len() * 2, 0);
+    }
+
+    let titles = window_titles();
+    pids.into_iter()
+        .filter_map(|pid| process_info(pid, &titles))
+        .filter(|process| process.name.eq_ignore_ascii_case(process_name))
+        .collect()
+}
+
+fn process_info(pid: u32, titles: &HashMap<u32, String>) -> Option<ProcessInfo> {
+    let handle = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid) };
+    if handle.is_null() {
+        return None;
+    }
+
+    let mut path = [0u16; 1024];
+    let mut path_len = path.len() as u32;
+    let mut times: [FILETIME; 4] = unsafe { std::mem::zeroed() };
+
+    let (has_path, has_times) = unsafe {
+        let has_path = QueryFullProcessImageNameW(handle, 0, path.as_mut_ptr(), &mut path_len) != 0;
+        let [creation, exit, kernel, user] = &mut times;
+        let has_times = GetProcessTimes(handle, creation, exit, kernel, user) != 0;
+        CloseHandle(handle);
+        (has_path, has_times)
+    };
+
+    if !has_path {
+        return None;
+    }
+    let path = PathBuf::from(OsString::from_wide(&path[..path_len as usize]));
+
+    Some(ProcessInfo {
+        pid,
+        name: path.file_name()?.to_string_lossy().into_owned(),
+        path: Some(path),
+        start_time: has_times.then(|| filetime_to_system_time(&times[0])),
+        window_title: titles.get(&pid).cloned(),
+    })
+}
+
+fn filetime_to_system_time(filetime: &FILETIME) -> SystemTime {
+    const WINDOWS_TO_UNIX_EPOCH: u64 = 11_644_473_600;
+
+    let intervals = (filetime.dwHighDateTime as u64) << 32 | filetime.dwLowDateTime as u64;
+    let since_windows_epoch = Duration::from_nanos(intervals.saturating_mul(100));
+    UNIX_EPOCH + since_windows_epoch.saturating_sub(Duration::from_secs(WINDOWS_TO_UNIX_EPOCH))
+}
+
+fn window_titles() -> HashMap<u32, String> {
+    unsafe extern "system" fn callback(hwnd: HWND, lparam: LPARAM) -> BOOL {
+        let titles = &mut *(lparam as *mut HashMap<u32, String>);
+        if IsWindowVisible(hwnd) == 0 {
+            return TRUE;
+        }
+
+        let mut title = [0u16; 256];
+        let len = GetWindowTextW(hwnd, title.as_mut_ptr(), title.len() as i32);
+        if len > 0 {
+            let mut pid = 0;
+            GetWindowThreadProcessId(hwnd, &mut pid);
+            titles
+                .entry(pid)
+                .or_insert_with(|| String::from_utf16_lossy(&title[..len as usize]));
+        }
+        TRUE
+    }
+
+    let mut titles = HashMap::new();
+    unsafe { EnumWindows(Some(callback), &mut titles as *mut _ as LPARAM) };
+    titles
+}
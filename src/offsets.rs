@@ -0,0 +1,254 @@
+use crate::memory::{self, Address, ModuleInfo, ProcessMemory};
+use crate::pe;
+use crate::signature::{ModuleImage, Signature, StaticPointer};
+use serde::Deserialize;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const BUILTIN_OFFSETS: &str = include_str!("../offsets.toml");
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct OffsetEntry {
+    pub name: String,
+    #[serde(default = "default_module")]
+    pub module: String,
+    pub timestamp: Option<u32>,
+    pub image_size: Option<u32>,
+    pub version: Option<String>,
+    pub base: Address,
+    pub offsets: Vec<usize>,
+    pub signature: Option<SignatureEntry>,
+    pub hook: Option<HookEntry>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct SignatureEntry {
+    pub pattern: String,
+    pub operand: usize,
+    pub length: usize,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct HookEntry {
+    #[serde(default = "default_hook_class")]
+    pub class: String,
+    pub position: Vec<usize>,
+    pub motion: Vec<usize>,
+    pub in_water: Vec<usize>,
+}
+
+#[derive(Default, Deserialize)]
+struct OffsetFile {
+    #[serde(default)]
+    build: Vec<OffsetEntry>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct OffsetDatabase {
+    entries: Vec<OffsetEntry>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GameBuild {
+    pub timestamp: Option<u32>,
+    pub image_size: Option<u32>,
+    pub version: Option<String>,
+}
+
+impl GameBuild {
+    pub fn read<M: ProcessMemory>(memory: &M, module: &ModuleInfo) -> Self {
+        GameBuild {
+            timestamp: pe::timestamp(memory, module.base).ok(),
+            image_size: pe::image_size(memory, module.base)
+                .ok()
+                .map(|size| size as u32),
+            version: pe::file_version(&module.path)
+                .ok()
+                .or_else(|| pe::image_version(memory, module.base).ok()),
+        }
+    }
+}
+
+impl fmt::Display for GameBuild {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(version) = &self.version {
+            write!(f, "version = \"{}\", ", version)?;
+        }
+        match (self.timestamp, self.image_size) {
+            (Some(timestamp), Some(image_size)) => write!(
+                f,
+                "timestamp = 0x{:08X}, image_size = 0x{:X}",
+                timestamp, image_size
+            ),
+            _ => write!(f, "unknown build"),
+        }
+    }
+}
+
+impl OffsetEntry {
+    pub fn is_fallback(&self) -> bool {
+        self.specificity() == 0
+    }
+
+    pub fn matches(&self, build: &GameBuild) -> bool {
+        (self.timestamp.is_none() || self.timestamp == build.timestamp)
+            && (self.image_size.is_none() || self.image_size == build.image_size)
+            && (self.version.is_none() || self.version == build.version)
+    }
+
+    pub fn static_pointer(&self) -> Option<StaticPointer> {
+        let signature = self.signature.as_ref()?;
+        Some(StaticPointer {
+            signature: signature.pattern.parse().ok()?,
+            operand_offset: signature.operand,
+            instruction_len: signature.length,
+        })
+    }
+
+    pub fn static_base<M: ProcessMemory>(&self, memory: &M, module: &ModuleInfo) -> Address {
+        let pointer = match self.static_pointer() {
+            Some(pointer) => pointer,
+            None => return module.base + self.base,
+        };
+
+        let image = ModuleImage::read(memory, module);
+        match pointer.resolve(&image) {
+            Some(address) => {
+                println!(
+                    "static base resolved from signature: {}+0x{:X}",
+                    self.module,
+                    address.wrapping_sub(module.base)
+                );
+                address
+            }
+            None => {
+                println!("signature scan failed, falling back to base offset");
+                module.base + self.base
+            }
+        }
+    }
+
+    fn specificity(&self) -> usize {
+        self.timestamp.is_some() as usize
+            + self.image_size.is_some() as usize
+            + self.version.is_some() as usize
+    }
+}
+
+impl OffsetDatabase {
+    pub fn load() -> Result<Self, String> {
+        let mut database = OffsetDatabase::default();
+        let pack_dir = pack_dir();
+
+        if let Ok(dir) = fs::read_dir(&pack_dir) {
+            let mut paths: Vec<PathBuf> = dir
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+                .collect();
+            paths.sort();
+
+            for path in paths {
+                let source = fs::read_to_string(&path)
+                    .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+                database.extend(&source, &path.display().to_string())?;
+            }
+        }
+
+        database.extend(BUILTIN_OFFSETS, "built-in offsets")?;
+        Ok(database)
+    }
+
+    fn extend(&mut self, source: &str, origin: &str) -> Result<(), String> {
+        let file: OffsetFile =
+            toml::from_str(source).map_err(|e| format!("invalid offsets in {}: {}", origin, e))?;
+
+        for entry in &file.build {
+            if let Some(signature) = &entry.signature {
+                signature.pattern.parse::<Signature>().map_err(|e| {
+                    format!(
+                        "invalid signature for '{}' in {}: {}",
+                        entry.name, origin, e
+                    )
+                })?;
+            }
+        }
+        self.entries.extend(file.build);
+        Ok(())
+    }
+
+    pub fn modules(&self) -> Vec<&str> {
+        let mut modules: Vec<&str> = Vec::new();
+        for entry in &self.entries {
+            if !modules.contains(&entry.module.as_str()) {
+                modules.push(&entry.module);
+            }
+        }
+        modules
+    }
+
+    pub fn resolve<M: ProcessMemory>(
+        &self,
+        memory: &M,
+    ) -> Result<(&OffsetEntry, ModuleInfo), String> {
+        let mut errors = Vec::new();
+        for module_name in self.modules() {
+            let module = match memory.find_module(module_name) {
+                Ok(module) => module,
+                Err(e) => {
+                    errors.push(format!(
+                        "failed to find module {}: {}",
+                        module_name,
+                        e.explain()
+                    ));
+                    continue;
+                }
+            };
+
+            let build = GameBuild::read(memory, &module);
+            match self.select(module_name, &build) {
+                Some(entry) => return Ok((entry, module)),
+                None => errors.push(format!(
+                    "no offsets entry matches {} ({}), add one to {}",
+                    module_name,
+                    build,
+                    pack_dir().display()
+                )),
+            }
+        }
+
+        if errors.is_empty() {
+            errors.push("offset database is empty".to_string());
+        }
+        Err(errors.join("; "))
+    }
+
+    pub fn select(&self, module: &str, build: &GameBuild) -> Option<&OffsetEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.module == module && entry.matches(build))
+            .fold(None, |best: Option<&OffsetEntry>, entry| match best {
+                Some(best) if best.specificity() >= entry.specificity() => Some(best),
+                _ => Some(entry),
+            })
+    }
+}
+
+pub fn pack_dir() -> PathBuf {
+    exe_dir().join("offsets")
+}
+
+pub fn exe_dir() -> PathBuf {
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf))
+        .unwrap_or_default()
+}
+
+fn default_module() -> String {
+    memory::DEFAULT_MODULE.to_string()
+}
+
+fn default_hook_class() -> String {
+    "FishingHook".to_string()
+}
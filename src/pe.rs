@@ -0,0 +1,275 @@
+use crate::binary::invalid_data;
+use crate::memory::{Address, MemoryError, ProcessMemory, Result};
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+
+const DOS_SIGNATURE: u16 = 0x5A4D;
+const NT_SIGNATURE: u32 = 0x0000_4550;
+const PE32_MAGIC: u16 = 0x10B;
+const PE32_PLUS_MAGIC: u16 = 0x20B;
+const OPTIONAL_HEADER: usize = 24;
+const HEADERS_SIZE: usize = 0x400;
+const SECTION_SIZE: usize = 40;
+const RESOURCE_DIRECTORY: usize = 2;
+const RT_VERSION: u32 = 16;
+const SUBDIRECTORY_FLAG: u32 = 0x8000_0000;
+const FIXED_FILE_INFO_SIGNATURE: u32 = 0xFEEF_04BD;
+const MAX_RESOURCE_SIZE: usize = 0x100_0000;
+const MAX_RESOURCE_ENTRIES: usize = 0x1000;
+const RESOURCE_LEVELS: usize = 3;
+
+enum ResourceEntry {
+    Directory(usize),
+    Data(usize),
+}
+
+pub fn nt_headers<M: ProcessMemory>(memory: &M, base: Address) -> Result<Address> {
+    if memory.read_le::<u16>(base)? != DOS_SIGNATURE {
+        return Err(MemoryError::InvalidData("missing MZ header".to_string()));
+    }
+
+    let nt = base + memory.read_le::<u32>(base + 0x3C)? as usize;
+    if memory.read_le::<u32>(nt)? != NT_SIGNATURE {
+        return Err(MemoryError::InvalidData("missing PE header".to_string()));
+    }
+    Ok(nt)
+}
+
+pub fn image_size<M: ProcessMemory>(memory: &M, base: Address) -> Result<usize> {
+    let nt = nt_headers(memory, base)?;
+    Ok(memory.read_le::<u32>(nt + 0x50)? as usize)
+}
+
+pub fn timestamp<M: ProcessMemory>(memory: &M, base: Address) -> Result<u32> {
+    let nt = nt_headers(memory, base)?;
+    memory.read_le::<u32>(nt + 0x8)
+}
+
+pub fn image_version<M: ProcessMemory>(memory: &M, base: Address) -> Result<String> {
+    let nt = nt_headers(memory, base)?;
+    let headers = memory.read_bytes(nt, HEADERS_SIZE)?;
+    let (rva, size) = resource_directory(&headers)
+        .ok_or_else(|| MemoryError::InvalidData("missing resource directory".to_string()))?;
+    let resources = memory.read_bytes(base + rva, size.min(MAX_RESOURCE_SIZE))?;
+    fixed_file_version(&resources, rva)
+        .ok_or_else(|| MemoryError::InvalidData("missing version resource".to_string()))
+}
+
+pub fn file_version(path: &Path) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut dos = [0u8; 0x40];
+    file.read_exact(&mut dos)?;
+    if u16_at(&dos, 0) != Some(DOS_SIGNATURE) {
+        return Err(invalid_data("missing MZ header"));
+    }
+
+    let nt = u32_at(&dos, 0x3C).unwrap_or_default() as u64;
+    let mut headers = vec![0u8; HEADERS_SIZE];
+    file.seek(SeekFrom::Start(nt))?;
+    let len = file.read(&mut headers)?;
+    headers.truncate(len);
+    if u32_at(&headers, 0) != Some(NT_SIGNATURE) {
+        return Err(invalid_data("missing PE header"));
+    }
+
+    let (rva, size) =
+        resource_directory(&headers).ok_or_else(|| invalid_data("missing resource directory"))?;
+    let offset =
+        file_offset(&headers, rva).ok_or_else(|| invalid_data("resource section not found"))?;
+    let mut resources = vec![0u8; size.min(MAX_RESOURCE_SIZE)];
+    file.seek(SeekFrom::Start(offset as u64))?;
+    file.read_exact(&mut resources)?;
+    fixed_file_version(&resources, rva).ok_or_else(|| invalid_data("missing version resource"))
+}
+
+fn resource_directory(headers: &[u8]) -> Option<(usize, usize)> {
+    let directories = match u16_at(headers, OPTIONAL_HEADER)? {
+        PE32_MAGIC => OPTIONAL_HEADER + 96,
+        PE32_PLUS_MAGIC => OPTIONAL_HEADER + 112,
+        _ => return None,
+    };
+    let entry = directories + RESOURCE_DIRECTORY * 8;
+    let rva = u32_at(headers, entry)? as usize;
+    let size = u32_at(headers, entry + 4)? as usize;
+    (rva != 0 && size != 0).then_some((rva, size))
+}
+
+fn file_offset(headers: &[u8], rva: usize) -> Option<usize> {
+    let count = u16_at(headers, 6)? as usize;
+    let sections = OPTIONAL_HEADER + u16_at(headers, 20)? as usize;
+    (0..count).find_map(|index| {
+        let section = sections + index * SECTION_SIZE;
+        let virtual_size = u32_at(headers, section + 8)? as usize;
+        let virtual_address = u32_at(headers, section + 12)? as usize;
+        let raw_offset = u32_at(headers, section + 20)? as usize;
+        (virtual_address..virtual_address + virtual_size)
+            .contains(&rva)
+            .then(|| raw_offset + rva - virtual_address)
+    })
+}
+
+fn fixed_file_version(resources: &[u8], resource_rva: usize) -> Option<String> {
+    let mut entry = resource_entries(resources, 0)?
+        .into_iter()
+        .find(|(id, _)| *id == RT_VERSION)?
+        .1;
+    let mut leaf = None;
+    for _ in 0..RESOURCE_LEVELS {
+        match entry {
+            ResourceEntry::Directory(offset) => {
+                entry = resource_entries(resources, offset)?.into_iter().next()?.1
+            }
+            ResourceEntry::Data(offset) => {
+                leaf = Some(offset);
+                break;
+            }
+        }
+    }
+    let leaf = leaf?;
+
+    let data = (u32_at(resources, leaf)? as usize).checked_sub(resource_rva)?;
+    let size = u32_at(resources, leaf + 4)? as usize;
+    let info = resources.get(data..data + size)?;
+
+    let fixed = (0..info.len())
+        .step_by(4)
+        .find(|&offset| u32_at(info, offset) == Some(FIXED_FILE_INFO_SIGNATURE))?;
+    let major = u32_at(info, fixed + 8)?;
+    let minor = u32_at(info, fixed + 12)?;
+    Some(format!(
+        "{}.{}.{}.{}",
+        major >> 16,
+        major & 0xFFFF,
+        minor >> 16,
+        minor & 0xFFFF
+    ))
+}
+
+fn resource_entries(resources: &[u8], directory: usize) -> Option<Vec<(u32, ResourceEntry)>> {
+    let count =
+        u16_at(resources, directory + 12)? as usize + u16_at(resources, directory + 14)? as usize;
+    (0..count.min(MAX_RESOURCE_ENTRIES))
+        .map(|index| {
+            let entry = directory + 16 + index * 8;
+            let id = u32_at(resources, entry)?;
+            let offset = u32_at(resources, entry + 4)?;
+            let offset = (offset & !SUBDIRECTORY_FLAG) as usize;
+            Some(if u32_at(resources, entry + 4)? & SUBDIRECTORY_FLAG != 0 {
+                (id, ResourceEntry::Directory(offset))
+            } else {
+                (id, ResourceEntry::Data(offset))
+            })
+        })
+        .collect()
+}
+
+fn u16_at(bytes: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(
+        bytes.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        bytes.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::FakeMemory;
+
+    const BASE: Address = 0x1_4000_0000;
+    const NT: usize = 0x80;
+    const RESOURCES: usize = 0x400;
+
+    fn put(bytes: &mut [u8], offset: usize, value: u32) {
+        bytes[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn resources() -> Vec<u8> {
+        let mut bytes = vec![0u8; 0x100];
+        for (directory, id, offset) in [
+            (0x00, RT_VERSION, SUBDIRECTORY_FLAG | 0x18),
+            (0x18, 1, SUBDIRECTORY_FLAG | 0x30),
+            (0x30, 0x409, 0x48),
+        ] {
+            bytes[directory + 14] = 1;
+            put(&mut bytes, directory + 16, id);
+            put(&mut bytes, directory + 20, offset);
+        }
+        put(&mut bytes, 0x48, (RESOURCES + 0x58) as u32);
+        put(&mut bytes, 0x4C, 0x60);
+        put(&mut bytes, 0x58 + 0x28, FIXED_FILE_INFO_SIGNATURE);
+        put(&mut bytes, 0x58 + 0x30, 1 << 16 | 21);
+        put(&mut bytes, 0x58 + 0x34, 2 << 16 | 2);
+        bytes
+    }
+
+    fn image() -> Vec<u8> {
+        let mut bytes = vec![0u8; 0x1000];
+        bytes[..2].copy_from_slice(&DOS_SIGNATURE.to_le_bytes());
+        put(&mut bytes, 0x3C, NT as u32);
+        put(&mut bytes, NT, NT_SIGNATURE);
+        bytes[NT + 6] = 1;
+        put(&mut bytes, NT + 8, 0x6650_1234);
+        bytes[NT + 20] = 0xF0;
+        bytes[NT + OPTIONAL_HEADER..][..2].copy_from_slice(&PE32_PLUS_MAGIC.to_le_bytes());
+        put(&mut bytes, NT + 0x50, 0x1000);
+        let directory = NT + OPTIONAL_HEADER + 112 + RESOURCE_DIRECTORY * 8;
+        put(&mut bytes, directory, RESOURCES as u32);
+        put(&mut bytes, directory + 4, 0x100);
+
+        let section = NT + OPTIONAL_HEADER + 0xF0;
+        put(&mut bytes, section + 8, 0x200);
+        put(&mut bytes, section + 12, RESOURCES as u32);
+        put(&mut bytes, section + 20, RESOURCES as u32);
+        bytes[RESOURCES..RESOURCES + 0x100].copy_from_slice(&resources());
+        bytes
+    }
+
+    #[test]
+    fn reads_the_loaded_image() {
+        let memory = FakeMemory::new();
+        memory.write(BASE, &image());
+        assert_eq!(nt_headers(&memory, BASE).unwrap(), BASE + NT);
+        assert_eq!(timestamp(&memory, BASE).unwrap(), 0x6650_1234);
+        assert_eq!(image_size(&memory, BASE).unwrap(), 0x1000);
+        assert_eq!(image_version(&memory, BASE).unwrap(), "1.21.2.2");
+
+        memory.put(BASE, 0u16);
+        assert!(image_version(&memory, BASE).is_err());
+    }
+
+    #[test]
+    fn reads_the_file_on_disk() {
+        let path = std::env::temp_dir().join(format!("mcbe-fisher-pe-{}.exe", std::process::id()));
+        std::fs::write(&path, image()).unwrap();
+        let version = file_version(&path);
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(version.unwrap(), "1.21.2.2");
+    }
+
+    #[test]
+    fn reads_the_fixed_file_info() {
+        assert_eq!(
+            fixed_file_version(&resources(), RESOURCES).as_deref(),
+            Some("1.21.2.2")
+        );
+        assert_eq!(fixed_file_version(&resources(), RESOURCES + 0x100), None);
+        assert_eq!(fixed_file_version(&resources()[..0x40], RESOURCES), None);
+    }
+
+    #[test]
+    fn stops_at_self_referencing_directories() {
+        let mut looped = resources();
+        put(&mut looped, 0x30 + 20, SUBDIRECTORY_FLAG | 0x30);
+        assert_eq!(fixed_file_version(&looped, RESOURCES), None);
+
+        let mut looped = resources();
+        put(&mut looped, 16 + 4, SUBDIRECTORY_FLAG);
+        assert_eq!(fixed_file_version(&looped, RESOURCES), None);
+    }
+}
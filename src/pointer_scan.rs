@@ -0,0 +1,290 @@
+use crate::binary;
+use crate::memory::{self, Address, ModuleInfo, ProcessMemory};
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::mem::size_of;
+use std::ops::Range;
+use std::path::Path;
+
+const MAGIC: &[u8; 4] = b"MCPM";
+const VERSION: u32 = 1;
+const POINTER_SIZE: usize = size_of::<usize>();
+
+pub struct ScanOptions {
+    pub max_depth: usize,
+    pub max_offset: usize,
+    pub max_results: usize,
+    pub module: Option<String>,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        ScanOptions {
+            max_depth: 5,
+            max_offset: 0x1000,
+            max_results: 100,
+            module: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PointerPath {
+    pub module: String,
+    pub base: Address,
+    pub offsets: Vec<usize>,
+}
+
+impl PointerPath {
+    pub fn to_entry(&self, name: &str) -> String {
+        let offsets: Vec<String> = self
+            .offsets
+            .iter()
+            .map(|offset| format!("0x{:X}", offset))
+            .collect();
+        format!(
+            "[[build]]\nname = \"{}\"\nmodule = \"{}\"\nbase = 0x{:08X}\noffsets = [{}]\n",
+            name,
+            self.module,
+            self.base,
+            offsets.join(", ")
+        )
+    }
+}
+
+impl fmt::Display for PointerPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "\"{}\"+0x{:X}", self.module, self.base)?;
+        for offset in &self.offsets {
+            write!(f, " -> 0x{:X}", offset)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn offsets_pack(target: Address, paths: &[PointerPath]) -> String {
+    let mut pack = format!(
+        "# pointer scan candidates for 0x{:X}\n# keep the chains that still resolve after a game restart\n",
+        target
+    );
+    for (index, path) in paths.iter().enumerate() {
+        pack.push('\n');
+        pack.push_str(&path.to_entry(&format!("pointer scan candidate {}", index + 1)));
+    }
+    pack
+}
+
+pub struct PointerMap {
+    pub modules: Vec<ModuleInfo>,
+    pointers: Vec<(Address, Address)>,
+}
+
+impl PointerMap {
+    pub fn capture<M: ProcessMemory>(memory: &M) -> memory::Result<Self> {
+        let modules = memory.modules()?;
+        let ranges = memory.readable_ranges()?;
+        let mut pointers = Vec::new();
+
+        for range in &ranges {
+            memory::read_chunks(memory, range.clone(), |address, bytes| {
+                for (index, word) in bytes.chunks_exact(POINTER_SIZE).enumerate() {
+                    let value = usize::from_le_bytes(word.try_into().unwrap());
+                    if is_mapped(&ranges, value) {
+                        pointers.push((value, address + index * POINTER_SIZE));
+                    }
+                }
+            });
+        }
+
+        pointers.sort_unstable();
+        Ok(PointerMap { modules, pointers })
+    }
+
+    pub fn pointer_count(&self) -> usize {
+        self.pointers.len()
+    }
+
+    pub fn scan(&self, target: Address, options: &ScanOptions) -> Vec<PointerPath> {
+        let mut results = Vec::new();
+        let mut offsets = Vec::new();
+
+        for depth in 1..=options.max_depth {
+            self.search(target, depth, options, &mut offsets, &mut results);
+            if results.len() >= options.max_results {
+                break;
+            }
+        }
+        results
+    }
+
+    fn search(
+        &self,
+        target: Address,
+        depth: usize,
+        options: &ScanOptions,
+        offsets: &mut Vec<usize>,
+        results: &mut Vec<PointerPath>,
+    ) {
+        for &(value, slot) in self.pointing_into(target, options.max_offset) {
+            if results.len() >= options.max_results {
+                return;
+            }
+
+            offsets.push(target - value);
+            match self.static_module(slot, options) {
+                Some(module) if offsets.len() == depth => {
+                    results.push(PointerPath {
+                        module: module.name.clone(),
+                        base: slot - module.base,
+                        offsets: offsets.iter().rev().copied().collect(),
+                    });
+                }
+                Some(_) => {}
+                None if offsets.len() < depth => {
+                    self.search(slot, depth, options, offsets, results);
+                }
+                None => {}
+            }
+            offsets.pop();
+        }
+    }
+
+    fn pointing_into(&self, target: Address, max_offset: usize) -> &[(Address, Address)] {
+        let low = target.saturating_sub(max_offset);
+        let start = self.pointers.partition_point(|&(value, _)| value < low);
+        let end = self.pointers.partition_point(|&(value, _)| value <= target);
+        &self.pointers[start..end]
+    }
+
+    fn static_module(&self, slot: Address, options: &ScanOptions) -> Option<&ModuleInfo> {
+        self.modules.iter().find(|module| {
+            (module.base..module.base + module.size).contains(&slot)
+                && options
+                    .module
+                    .as_ref()
+                    .is_none_or(|name| module.name.eq_ignore_ascii_case(name))
+        })
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        binary::write_header(&mut writer, MAGIC, VERSION)?;
+        binary::write_modules(&mut writer, &self.modules)?;
+
+        binary::write_u64(&mut writer, self.pointers.len() as u64)?;
+        for &(value, slot) in &self.pointers {
+            binary::write_u64(&mut writer, value as u64)?;
+            binary::write_u64(&mut writer, slot as u64)?;
+        }
+        writer.flush()
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+        binary::read_header(&mut reader, MAGIC, VERSION, "pointer map")?;
+        let modules = binary::read_modules(&mut reader)?;
+
+        let pointer_count = binary::read_u64(&mut reader)?;
+        let mut pointers = Vec::new();
+        for _ in 0..pointer_count {
+            let value = binary::read_u64(&mut reader)? as Address;
+            let slot = binary::read_u64(&mut reader)? as Address;
+            pointers.push((value, slot));
+        }
+
+        Ok(PointerMap { modules, pointers })
+    }
+}
+
+fn is_mapped(ranges: &[Range<Address>], address: Address) -> bool {
+    let index = ranges.partition_point(|range| range.end <= address);
+    ranges
+        .get(index)
+        .is_some_and(|range| range.contains(&address))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::FakeMemory;
+
+    const MODULE_BASE: Address = 0x1_4000_0000;
+    const HEAP: Address = 0x2000_0000;
+    const TARGET: Address = 0x3000_000C;
+
+    fn pointer_map() -> PointerMap {
+        let mut memory = FakeMemory::new();
+        memory.add_module("Minecraft.Windows.exe", MODULE_BASE, 0x1000);
+        memory.put(MODULE_BASE + 0x100, HEAP);
+        memory.put(HEAP, [0, 0, 0x3000_0000usize]);
+        memory.put(0x3000_0000, [0u32; 4]);
+        PointerMap::capture(&memory).unwrap()
+    }
+
+    fn offsets(paths: &[PointerPath]) -> Vec<(usize, Vec<usize>)> {
+        paths
+            .iter()
+            .map(|path| (path.base, path.offsets.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn finds_a_static_chain() {
+        let map = pointer_map();
+        assert_eq!(map.pointer_count(), 2);
+
+        let paths = map.scan(TARGET, &ScanOptions::default());
+        assert_eq!(offsets(&paths), vec![(0x100, vec![0x10, 0xC])]);
+        assert_eq!(paths[0].module, "Minecraft.Windows.exe");
+        assert_eq!(
+            paths[0].to_string(),
+            "\"Minecraft.Windows.exe\"+0x100 -> 0x10 -> 0xC"
+        );
+    }
+
+    #[test]
+    fn respects_the_scan_limits() {
+        let map = pointer_map();
+        let scan = |options: ScanOptions| map.scan(TARGET, &options);
+
+        assert!(scan(ScanOptions {
+            max_depth: 1,
+            ..Default::default()
+        })
+        .is_empty());
+        assert!(scan(ScanOptions {
+            max_offset: 0xC,
+            ..Default::default()
+        })
+        .is_empty());
+        assert!(scan(ScanOptions {
+            module: Some("other.dll".to_string()),
+            ..Default::default()
+        })
+        .is_empty());
+        assert_eq!(
+            scan(ScanOptions {
+                max_offset: 0x10,
+                ..Default::default()
+            })
+            .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn round_trips_a_saved_map() {
+        let path = std::env::temp_dir().join(format!("mcbe-fisher-map-{}.bin", std::process::id()));
+        pointer_map().save(&path).unwrap();
+        let map = PointerMap::load(&path);
+        let _ = std::fs::remove_file(&path);
+
+        let map = map.unwrap();
+        assert_eq!(map.modules.len(), 1);
+        assert_eq!(
+            offsets(&map.scan(TARGET, &ScanOptions::default())),
+            vec![(0x100, vec![0x10, 0xC])]
+        );
+    }
+}
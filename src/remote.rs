@@ -0,0 +1,540 @@
+use crate::memory::{Address, MemoryError, ProcessMemory, Result};
+use bytemuck::{Pod, Zeroable};
+use std::mem::{offset_of, size_of};
+
+const SSO_CAPACITY: usize = 15;
+const MAX_STRING_LEN: usize = 0x10000;
+const MAX_ELEMENTS: usize = 0x10_0000;
+const LIST_NODE_VALUE: usize = 0x10;
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct RawString {
+    buffer: [u8; 16],
+    size: usize,
+    capacity: usize,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct RawVector {
+    first: Address,
+    last: Address,
+    end: Address,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct RawUnorderedMap {
+    max_load_factor: [u8; 8],
+    head: Address,
+    size: usize,
+    buckets: RawVector,
+    mask: usize,
+    max_index: usize,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct RawListNode {
+    next: Address,
+    prev: Address,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct RawSharedPtr {
+    pointer: Address,
+    control: Address,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct SharedPtr {
+    pub pointer: Address,
+    pub control: Address,
+    pub uses: u32,
+    pub weaks: u32,
+}
+
+pub fn read_string<M: ProcessMemory>(memory: &M, address: Address) -> Result<String> {
+    let raw = memory.read::<RawString>(address)?;
+    if raw.size > raw.capacity || raw.capacity < SSO_CAPACITY {
+        return Err(invalid(
+            "std::string",
+            address,
+            format!("size {} and capacity {}", raw.size, raw.capacity),
+        ));
+    }
+    if raw.size > MAX_STRING_LEN {
+        return Err(invalid(
+            "std::string",
+            address,
+            format!("size {} is too large", raw.size),
+        ));
+    }
+
+    let bytes = if raw.capacity == SSO_CAPACITY {
+        raw.buffer[..raw.size].to_vec()
+    } else {
+        let data = usize::from_le_bytes(raw.buffer[..8].try_into().unwrap());
+        memory.read_bytes(data, raw.size)?
+    };
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+pub fn vector_elements<M: ProcessMemory>(
+    memory: &M,
+    address: Address,
+    element_size: usize,
+) -> Result<Vec<Address>> {
+    let (first, count) = vector_range(memory, address, element_size)?;
+    Ok((0..count)
+        .map(|index| first + index * element_size)
+        .collect())
+}
+
+pub fn read_vector<M: ProcessMemory, T: Pod>(memory: &M, address: Address) -> Result<Vec<T>> {
+    let (first, count) = vector_range(memory, address, size_of::<T>())?;
+    memory.read_slice(first, count)
+}
+
+fn vector_range<M: ProcessMemory>(
+    memory: &M,
+    address: Address,
+    element_size: usize,
+) -> Result<(Address, usize)> {
+    let raw = memory.read::<RawVector>(address)?;
+    if raw.first > raw.last || raw.last > raw.end {
+        return Err(invalid(
+            "std::vector",
+            address,
+            format!(
+                "pointers 0x{:X}, 0x{:X}, 0x{:X} are out of order",
+                raw.first, raw.last, raw.end
+            ),
+        ));
+    }
+
+    let len = raw.last - raw.first;
+    if element_size == 0 || len % element_size != 0 {
+        return Err(invalid(
+            "std::vector",
+            address,
+            format!("length 0x{:X} is not a multiple of {}", len, element_size),
+        ));
+    }
+    let count = len / element_size;
+    if count > MAX_ELEMENTS {
+        return Err(invalid(
+            "std::vector",
+            address,
+            format!("{} elements is too many", count),
+        ));
+    }
+    Ok((raw.first, count))
+}
+
+pub fn unordered_map_entries<M: ProcessMemory>(
+    memory: &M,
+    address: Address,
+) -> Result<Vec<Address>> {
+    let raw = read_map(memory, address)?;
+    let mut entries = Vec::with_capacity(raw.size);
+    let mut node = memory.read::<RawListNode>(raw.head)?.next;
+    while node != raw.head {
+        if entries.len() == raw.size || node == 0 {
+            return Err(invalid(
+                "std::unordered_map",
+                address,
+                format!("node list does not match size {}", raw.size),
+            ));
+        }
+        entries.push(node + LIST_NODE_VALUE);
+        node = memory.read::<RawListNode>(node)?.next;
+    }
+
+    if entries.len() != raw.size {
+        return Err(invalid(
+            "std::unordered_map",
+            address,
+            format!("found {} nodes instead of {}", entries.len(), raw.size),
+        ));
+    }
+    Ok(entries)
+}
+
+pub fn unordered_map_bucket<M: ProcessMemory>(
+    memory: &M,
+    address: Address,
+    bucket: usize,
+) -> Result<Vec<Address>> {
+    let raw = read_map(memory, address)?;
+    if bucket >= raw.max_index {
+        return Err(invalid(
+            "std::unordered_map",
+            address,
+            format!("bucket {} of {}", bucket, raw.max_index),
+        ));
+    }
+    let buckets = address + offset_of!(RawUnorderedMap, buckets);
+    let (first, count) = vector_range(memory, buckets, size_of::<Address>())?;
+    if count != raw.max_index * 2 {
+        return Err(invalid(
+            "std::unordered_map",
+            address,
+            format!("{} bucket pointers for {} buckets", count, raw.max_index),
+        ));
+    }
+
+    let [low, high] = memory.read::<[Address; 2]>(first + bucket * 2 * size_of::<Address>())?;
+    let mut entries = Vec::new();
+    if low == raw.head {
+        return Ok(entries);
+    }
+    let mut node = low;
+    loop {
+        if entries.len() == raw.size || node == 0 || node == raw.head {
+            return Err(invalid(
+                "std::unordered_map",
+                address,
+                format!("bucket {} does not end at 0x{:X}", bucket, high),
+            ));
+        }
+        entries.push(node + LIST_NODE_VALUE);
+        if node == high {
+            return Ok(entries);
+        }
+        node = memory.read::<RawListNode>(node)?.next;
+    }
+}
+
+fn read_map<M: ProcessMemory>(memory: &M, address: Address) -> Result<RawUnorderedMap> {
+    let raw = memory.read::<RawUnorderedMap>(address)?;
+    if raw.head == 0 || raw.size > MAX_ELEMENTS {
+        return Err(invalid(
+            "std::unordered_map",
+            address,
+            format!("head 0x{:X} and size {}", raw.head, raw.size),
+        ));
+    }
+    if raw.mask.wrapping_add(1) != raw.max_index {
+        return Err(invalid(
+            "std::unordered_map",
+            address,
+            format!("mask 0x{:X} and bucket count {}", raw.mask, raw.max_index),
+        ));
+    }
+    Ok(raw)
+}
+
+pub fn read_shared_ptr<M: ProcessMemory>(
+    memory: &M,
+    address: Address,
+) -> Result<Option<SharedPtr>> {
+    let raw = memory.read::<RawSharedPtr>(address)?;
+    match (raw.pointer, raw.control) {
+        (0, 0) => return Ok(None),
+        (_, 0) => {
+            return Err(invalid(
+                "std::shared_ptr",
+                address,
+                format!("pointer 0x{:X} has no control block", raw.pointer),
+            ))
+        }
+        _ => {}
+    }
+
+    let uses = memory.read_le::<u32>(raw.control + 0x8)?;
+    let weaks = memory.read_le::<u32>(raw.control + 0xC)?;
+    if weaks == 0 {
+        return Err(invalid(
+            "std::shared_ptr",
+            address,
+            format!("control block 0x{:X} has no weak count", raw.control),
+        ));
+    }
+
+    Ok(Some(SharedPtr {
+        pointer: raw.pointer,
+        control: raw.control,
+        uses,
+        weaks,
+    }))
+}
+
+fn invalid(kind: &str, address: Address, problem: String) -> MemoryError {
+    MemoryError::InvalidData(format!("{} at 0x{:X}: {}", kind, address, problem))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::FakeMemory;
+
+    const OBJECT: Address = 0x1000;
+    const DATA: Address = 0x2000;
+    const HEAD: Address = 0x3000;
+    const FIRST: Address = 0x3100;
+    const SECOND: Address = 0x3200;
+    const BUCKETS: Address = 0x4000;
+
+    fn string(buffer: &[u8], size: usize, capacity: usize) -> RawString {
+        let mut raw = RawString {
+            buffer: [0; 16],
+            size,
+            capacity,
+        };
+        raw.buffer[..buffer.len()].copy_from_slice(buffer);
+        raw
+    }
+
+    fn map(size: usize) -> FakeMemory {
+        let memory = FakeMemory::new();
+        memory.put(
+            HEAD,
+            RawListNode {
+                next: FIRST,
+                prev: SECOND,
+            },
+        );
+        memory.put(
+            FIRST,
+            RawListNode {
+                next: SECOND,
+                prev: HEAD,
+            },
+        );
+        memory.put(
+            SECOND,
+            RawListNode {
+                next: HEAD,
+                prev: FIRST,
+            },
+        );
+
+        let mut buckets = [HEAD; 16];
+        buckets[6] = FIRST;
+        buckets[7] = SECOND;
+        memory.put(BUCKETS, buckets);
+        memory.put(
+            OBJECT,
+            RawUnorderedMap {
+                max_load_factor: [0; 8],
+                head: HEAD,
+                size,
+                buckets: RawVector {
+                    first: BUCKETS,
+                    last: BUCKETS + 16 * 8,
+                    end: BUCKETS + 16 * 8,
+                },
+                mask: 7,
+                max_index: 8,
+            },
+        );
+        memory
+    }
+
+    #[test]
+    fn reads_small_strings_inline() {
+        let memory = FakeMemory::new();
+        memory.put(OBJECT, string(b"cod", 3, SSO_CAPACITY));
+        assert_eq!(read_string(&memory, OBJECT).unwrap(), "cod");
+    }
+
+    #[test]
+    fn reads_long_strings_from_the_heap() {
+        let memory = FakeMemory::new();
+        let text = b"minecraft:tropical_fish";
+        memory.write(DATA, text);
+        memory.put(OBJECT, string(&DATA.to_le_bytes(), text.len(), 31));
+        assert_eq!(
+            read_string(&memory, OBJECT).unwrap(),
+            "minecraft:tropical_fish"
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_strings() {
+        let memory = FakeMemory::new();
+        memory.put(OBJECT, string(b"", 20, SSO_CAPACITY));
+        assert!(read_string(&memory, OBJECT).is_err());
+
+        memory.put(OBJECT, string(b"", 3, 7));
+        assert!(read_string(&memory, OBJECT).is_err());
+
+        memory.put(
+            OBJECT,
+            string(&DATA.to_le_bytes(), MAX_STRING_LEN + 1, MAX_STRING_LEN * 2),
+        );
+        assert!(read_string(&memory, OBJECT).is_err());
+    }
+
+    #[test]
+    fn reads_vectors() {
+        let memory = FakeMemory::new();
+        memory.put(DATA, [1u32, 2, 3]);
+        memory.put(
+            OBJECT,
+            RawVector {
+                first: DATA,
+                last: DATA + 12,
+                end: DATA + 16,
+            },
+        );
+        assert_eq!(
+            read_vector::<_, u32>(&memory, OBJECT).unwrap(),
+            vec![1, 2, 3]
+        );
+        assert_eq!(
+            vector_elements(&memory, OBJECT, 4).unwrap(),
+            vec![DATA, DATA + 4, DATA + 8]
+        );
+        assert!(vector_elements(&memory, OBJECT, 8).is_err());
+        assert!(vector_elements(&memory, OBJECT, 0).is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_vectors() {
+        let memory = FakeMemory::new();
+        memory.put(
+            OBJECT,
+            RawVector {
+                first: DATA + 8,
+                last: DATA,
+                end: DATA + 16,
+            },
+        );
+        assert!(read_vector::<_, u32>(&memory, OBJECT).is_err());
+
+        memory.put(
+            OBJECT,
+            RawVector {
+                first: DATA,
+                last: DATA + 16,
+                end: DATA + 8,
+            },
+        );
+        assert!(read_vector::<_, u32>(&memory, OBJECT).is_err());
+
+        memory.put(
+            OBJECT,
+            RawVector {
+                first: DATA,
+                last: DATA + MAX_ELEMENTS + 1,
+                end: DATA + MAX_ELEMENTS + 1,
+            },
+        );
+        assert!(vector_elements(&memory, OBJECT, 1).is_err());
+    }
+
+    #[test]
+    fn walks_the_map_node_list() {
+        let memory = map(2);
+        assert_eq!(
+            unordered_map_entries(&memory, OBJECT).unwrap(),
+            vec![FIRST + LIST_NODE_VALUE, SECOND + LIST_NODE_VALUE]
+        );
+    }
+
+    #[test]
+    fn walks_a_map_bucket() {
+        let memory = map(2);
+        assert_eq!(
+            unordered_map_bucket(&memory, OBJECT, 3).unwrap(),
+            vec![FIRST + LIST_NODE_VALUE, SECOND + LIST_NODE_VALUE]
+        );
+        assert!(unordered_map_bucket(&memory, OBJECT, 0).unwrap().is_empty());
+        assert!(unordered_map_bucket(&memory, OBJECT, 8).is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_maps() {
+        let memory = map(3);
+        assert!(unordered_map_entries(&memory, OBJECT).is_err());
+
+        let memory = map(MAX_ELEMENTS + 1);
+        memory.put(
+            SECOND,
+            RawListNode {
+                next: FIRST,
+                prev: FIRST,
+            },
+        );
+        memory.put(BUCKETS + 7 * size_of::<Address>(), 0x9000usize);
+        assert!(unordered_map_entries(&memory, OBJECT).is_err());
+        assert!(unordered_map_bucket(&memory, OBJECT, 3).is_err());
+
+        let memory = map(2);
+        memory.put(
+            SECOND,
+            RawListNode {
+                next: FIRST,
+                prev: FIRST,
+            },
+        );
+        assert!(unordered_map_entries(&memory, OBJECT).is_err());
+        assert!(unordered_map_bucket(&memory, OBJECT, 3).is_ok());
+
+        let memory = map(2);
+        memory.put(
+            FIRST,
+            RawListNode {
+                next: 0,
+                prev: HEAD,
+            },
+        );
+        assert!(unordered_map_entries(&memory, OBJECT).is_err());
+        assert!(unordered_map_bucket(&memory, OBJECT, 3).is_err());
+
+        let memory = map(2);
+        memory.put(OBJECT + offset_of!(RawUnorderedMap, mask), 3usize);
+        assert!(unordered_map_entries(&memory, OBJECT).is_err());
+    }
+
+    #[test]
+    fn reads_shared_pointers() {
+        let memory = FakeMemory::new();
+        memory.put(
+            OBJECT,
+            RawSharedPtr {
+                pointer: 0,
+                control: 0,
+            },
+        );
+        assert!(read_shared_ptr(&memory, OBJECT).unwrap().is_none());
+
+        memory.put(DATA, [0usize, 0x0000_0001_0000_0002]);
+        memory.put(
+            OBJECT,
+            RawSharedPtr {
+                pointer: 0x5000,
+                control: DATA,
+            },
+        );
+        let shared = read_shared_ptr(&memory, OBJECT).unwrap().unwrap();
+        assert_eq!((shared.pointer, shared.uses, shared.weaks), (0x5000, 2, 1));
+    }
+
+    #[test]
+    fn rejects_malformed_shared_pointers() {
+        let memory = FakeMemory::new();
+        memory.put(
+            OBJECT,
+            RawSharedPtr {
+                pointer: 0x5000,
+                control: 0,
+            },
+        );
+        assert!(read_shared_ptr(&memory, OBJECT).is_err());
+
+        memory.put(DATA, [0usize, 2]);
+        memory.put(
+            OBJECT,
+            RawSharedPtr {
+                pointer: 0x5000,
+                control: DATA,
+            },
+        );
+        assert!(read_shared_ptr(&memory, OBJECT).is_err());
+    }
+}
@@ -0,0 +1,243 @@
+use crate::memory::{self, Address, ModuleInfo, ProcessMemory};
+use crate::signature::ModuleImage;
+use std::collections::HashMap;
+use std::ops::Range;
+
+const POINTER_SIZE: usize = 8;
+const TYPE_NAME_OFFSET: usize = 0x10;
+const LOCATOR_SIZE: usize = 24;
+const LOCATOR_SIGNATURE: u32 = 1;
+const MAX_NAME_LEN: usize = 0x400;
+const NAME_PREFIXES: [&[u8]; 2] = [b".?AV", b".?AU"];
+
+#[derive(Clone, Debug)]
+pub struct RttiClass {
+    pub name: String,
+    pub type_descriptor: Address,
+    pub vtables: Vec<VTable>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct VTable {
+    pub address: Address,
+    pub locator: Address,
+    pub offset: u32,
+}
+
+impl RttiClass {
+    pub fn find(image: &ModuleImage, name: &str) -> Option<Self> {
+        decorations(name).into_iter().find_map(|decorated| {
+            let needle = [decorated.as_bytes(), b"\0"].concat();
+            let offset = image
+                .bytes
+                .windows(needle.len())
+                .position(|window| window == needle.as_slice())?;
+            let type_descriptor = offset.checked_sub(TYPE_NAME_OFFSET)?;
+
+            Some(RttiClass {
+                name: decorated,
+                type_descriptor: image.base + type_descriptor,
+                vtables: find_vtables(image, type_descriptor),
+            })
+        })
+    }
+
+    pub fn primary_vtable(&self) -> Option<Address> {
+        self.vtables
+            .iter()
+            .find(|vtable| vtable.offset == 0)
+            .map(|vtable| vtable.address)
+    }
+}
+
+pub fn list_classes(image: &ModuleImage) -> Vec<(String, Address)> {
+    let mut classes = Vec::new();
+    for prefix in NAME_PREFIXES {
+        let mut start = 0;
+        while let Some(found) = image.bytes[start..]
+            .windows(prefix.len())
+            .position(|window| window == prefix)
+        {
+            let offset = start + found;
+            start = offset + prefix.len();
+            if offset < TYPE_NAME_OFFSET {
+                continue;
+            }
+            if let Some(name) = read_name(&image.bytes[offset..]) {
+                classes.push((name, image.base + offset - TYPE_NAME_OFFSET));
+            }
+        }
+    }
+    classes.sort();
+    classes
+}
+
+pub fn find_objects<M: ProcessMemory>(
+    memory: &M,
+    module: &ModuleInfo,
+    vtable: Address,
+    max_results: usize,
+) -> memory::Result<Vec<Address>> {
+    let ranges = memory.readable_ranges()?;
+    Ok(find_objects_in(memory, module, ranges, vtable, max_results))
+}
+
+pub fn find_objects_in<M: ProcessMemory>(
+    memory: &M,
+    module: &ModuleInfo,
+    ranges: impl IntoIterator<Item = Range<Address>>,
+    vtable: Address,
+    max_results: usize,
+) -> Vec<Address> {
+    let image = module.base..module.base + module.size;
+    let mut objects = Vec::new();
+
+    for range in ranges {
+        if objects.len() >= max_results {
+            break;
+        }
+        let before = range.start..range.end.min(image.start);
+        let after = range.start.max(image.end)..range.end;
+        for part in [before, after] {
+            if part.is_empty() {
+                continue;
+            }
+            memory::read_chunks(memory, part, |address, bytes| {
+                for (index, word) in bytes.chunks_exact(POINTER_SIZE).enumerate() {
+                    if objects.len() < max_results
+                        && usize::from_le_bytes(word.try_into().unwrap()) == vtable
+                    {
+                        objects.push(address + index * POINTER_SIZE);
+                    }
+                }
+            });
+        }
+    }
+    objects
+}
+
+fn find_vtables(image: &ModuleImage, type_descriptor: usize) -> Vec<VTable> {
+    let mut locators = HashMap::new();
+    for (index, locator) in image.bytes.chunks_exact(4).enumerate() {
+        let offset = index * 4;
+        if u32_at(locator, 0) != LOCATOR_SIGNATURE {
+            continue;
+        }
+        let locator = match image.bytes.get(offset..offset + LOCATOR_SIZE) {
+            Some(locator) => locator,
+            None => break,
+        };
+        if u32_at(locator, 12) as usize == type_descriptor && u32_at(locator, 20) as usize == offset
+        {
+            locators.insert(image.base + offset, u32_at(locator, 4));
+        }
+    }
+    if locators.is_empty() {
+        return Vec::new();
+    }
+
+    let mut vtables = Vec::new();
+    for (slot, word) in image.bytes.chunks_exact(POINTER_SIZE).enumerate() {
+        let address = usize::from_le_bytes(word.try_into().unwrap());
+        if let Some(&offset) = locators.get(&address) {
+            vtables.push(VTable {
+                address: image.base + (slot + 1) * POINTER_SIZE,
+                locator: address,
+                offset,
+            });
+        }
+    }
+    vtables.sort_by_key(|vtable| vtable.offset);
+    vtables
+}
+
+fn read_name(bytes: &[u8]) -> Option<String> {
+    let len = bytes.iter().take(MAX_NAME_LEN).position(|&b| b == 0)?;
+    let name = std::str::from_utf8(&bytes[..len]).ok()?;
+    (name.ends_with("@@") && name.bytes().all(|b| b.is_ascii_graphic())).then(|| name.to_string())
+}
+
+fn decorations(name: &str) -> Vec<String> {
+    if name.starts_with(".?A") {
+        return vec![name.to_string()];
+    }
+    NAME_PREFIXES
+        .iter()
+        .map(|prefix| format!("{}{}@@", String::from_utf8_lossy(prefix), name))
+        .collect()
+}
+
+pub fn undecorate(name: &str) -> &str {
+    name.get(4..)
+        .and_then(|name| name.strip_suffix("@@"))
+        .unwrap_or(name)
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::FakeMemory;
+
+    const BASE: Address = 0x1_4000_0000;
+    const TYPE_DESCRIPTOR: usize = 0x40;
+
+    fn image() -> ModuleImage {
+        let mut bytes = vec![0u8; 0x200];
+        let name = b".?AVFishingHook@@\0";
+        bytes[TYPE_DESCRIPTOR + TYPE_NAME_OFFSET..][..name.len()].copy_from_slice(name);
+
+        for (locator, offset, slot) in [(0x100, 0x10, 0x1A0), (0x120, 0, 0x180)] {
+            for (field, value) in [(0, 1u32), (4, offset), (12, 0x40), (20, locator as u32)] {
+                bytes[locator + field..][..4].copy_from_slice(&value.to_le_bytes());
+            }
+            bytes[slot..][..8].copy_from_slice(&(BASE + locator).to_le_bytes());
+        }
+        ModuleImage { base: BASE, bytes }
+    }
+
+    #[test]
+    fn finds_classes_and_vtables() {
+        let image = image();
+        assert_eq!(
+            list_classes(&image),
+            vec![(".?AVFishingHook@@".to_string(), BASE + TYPE_DESCRIPTOR)]
+        );
+
+        let class = RttiClass::find(&image, "FishingHook").unwrap();
+        assert_eq!(class.type_descriptor, BASE + TYPE_DESCRIPTOR);
+        let vtables: Vec<(Address, u32)> = class
+            .vtables
+            .iter()
+            .map(|vtable| (vtable.address, vtable.offset))
+            .collect();
+        assert_eq!(vtables, vec![(BASE + 0x188, 0), (BASE + 0x1A8, 0x10)]);
+        assert_eq!(class.primary_vtable(), Some(BASE + 0x188));
+        assert!(RttiClass::find(&image, "Player").is_none());
+    }
+
+    #[test]
+    fn finds_objects_next_to_the_image() {
+        let mut memory = FakeMemory::new();
+        memory.add_module("Minecraft.Windows.exe", BASE, 0x200);
+        let module = memory.find_module("Minecraft.Windows.exe").unwrap();
+        let vtable = BASE + 0x188;
+
+        memory.put(BASE + 0x1F0, vtable);
+        memory.put(BASE + 0x200, [vtable, 0]);
+        memory.put(0x2000_0000, [0, vtable]);
+
+        let objects = find_objects(&memory, &module, vtable, 16).unwrap();
+        assert_eq!(objects, vec![0x2000_0008, BASE + 0x200]);
+        assert_eq!(find_objects(&memory, &module, vtable, 1).unwrap().len(), 1);
+
+        let heap = 0x2000_0000..0x2000_0010;
+        assert_eq!(
+            find_objects_in(&memory, &module, std::iter::once(heap), vtable, 16),
+            vec![0x2000_0008]
+        );
+    }
+}
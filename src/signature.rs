@@ -0,0 +1,213 @@
+use crate::memory::{Address, ModuleInfo, ProcessMemory};
+use std::fs;
+use std::io::Result;
+use std::path::Path;
+use std::str::FromStr;
+
+const CHUNK_SIZE: usize = 0x10000;
+const PAGE_SIZE: usize = 0x1000;
+
+#[derive(Clone, Debug)]
+pub struct Signature {
+    bytes: Vec<Option<u8>>,
+}
+
+impl Signature {
+    pub fn find(&self, haystack: &[u8]) -> Option<usize> {
+        self.find_from(haystack, 0)
+    }
+
+    pub fn find_all(&self, haystack: &[u8]) -> Vec<usize> {
+        let mut matches = Vec::new();
+        let mut start = 0;
+        while let Some(offset) = self.find_from(haystack, start) {
+            matches.push(offset);
+            start = offset + 1;
+        }
+        matches
+    }
+
+    fn find_from(&self, haystack: &[u8], start: usize) -> Option<usize> {
+        if haystack.len() < self.bytes.len() {
+            return None;
+        }
+        let last = haystack.len() - self.bytes.len();
+        let anchor = self.bytes.iter().position(Option::is_some);
+
+        let mut offset = start;
+        while offset <= last {
+            if let Some(index) = anchor {
+                let byte = self.bytes[index].unwrap();
+                let window = &haystack[offset + index..=last + index];
+                offset += window.iter().position(|&b| b == byte)?;
+            }
+            if self.matches_at(haystack, offset) {
+                return Some(offset);
+            }
+            offset += 1;
+        }
+        None
+    }
+
+    fn matches_at(&self, haystack: &[u8], offset: usize) -> bool {
+        self.bytes
+            .iter()
+            .zip(&haystack[offset..])
+            .all(|(expected, &actual)| expected.is_none_or(|b| b == actual))
+    }
+}
+
+impl FromStr for Signature {
+    type Err = String;
+
+    fn from_str(pattern: &str) -> std::result::Result<Self, Self::Err> {
+        let bytes = pattern
+            .split_whitespace()
+            .map(|token| match token {
+                "?" | "??" => Ok(None),
+                _ => u8::from_str_radix(token, 16)
+                    .map(Some)
+                    .map_err(|_| format!("invalid signature byte: {}", token)),
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        if bytes.iter().all(Option::is_none) {
+            return Err("signature must contain at least one concrete byte".to_string());
+        }
+        Ok(Signature { bytes })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct StaticPointer {
+    pub signature: Signature,
+    pub operand_offset: usize,
+    pub instruction_len: usize,
+}
+
+impl StaticPointer {
+    pub fn resolve(&self, image: &ModuleImage) -> Option<Address> {
+        let offset = self.signature.find(&image.bytes)?;
+        image.resolve_rip(offset, self.operand_offset, self.instruction_len)
+    }
+}
+
+pub struct ModuleImage {
+    pub base: Address,
+    pub bytes: Vec<u8>,
+}
+
+impl ModuleImage {
+    pub fn read<M: ProcessMemory>(memory: &M, module: &ModuleInfo) -> Self {
+        let mut bytes = vec![0u8; module.size];
+
+        for (index, chunk) in bytes.chunks_mut(CHUNK_SIZE).enumerate() {
+            let address = module.base + index * CHUNK_SIZE;
+            if memory.read_raw(address, chunk).is_err() {
+                for (page_index, page) in chunk.chunks_mut(PAGE_SIZE).enumerate() {
+                    let _ = memory.read_raw(address + page_index * PAGE_SIZE, page);
+                }
+            }
+        }
+
+        ModuleImage {
+            base: module.base,
+            bytes,
+        }
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P, base: Address) -> Result<Self> {
+        Ok(ModuleImage {
+            base,
+            bytes: fs::read(path)?,
+        })
+    }
+
+    pub fn resolve_rip(
+        &self,
+        offset: usize,
+        operand_offset: usize,
+        instruction_len: usize,
+    ) -> Option<Address> {
+        let start = offset + operand_offset;
+        let operand = self.bytes.get(start..start + 4)?;
+        let displacement = i32::from_le_bytes(operand.try_into().ok()?);
+        let next_instruction = self.base + offset + instruction_len;
+        Some(next_instruction.wrapping_add_signed(displacement as isize))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: Address = 0x1_4000_0000;
+
+    fn image() -> ModuleImage {
+        let mut bytes = vec![0xCCu8; 0x40];
+        bytes[0x10..0x17].copy_from_slice(&[0x48, 0x8B, 0x05, 0x20, 0x01, 0x00, 0x00]);
+        bytes[0x30..0x37].copy_from_slice(&[0x48, 0x8B, 0x05, 0xF0, 0xFF, 0xFF, 0xFF]);
+        ModuleImage { base: BASE, bytes }
+    }
+
+    #[test]
+    fn parses_wildcards() {
+        let signature: Signature = "48 8B ?? ? 05".parse().unwrap();
+        assert_eq!(
+            signature.bytes,
+            vec![Some(0x48), Some(0x8B), None, None, Some(0x05)]
+        );
+    }
+
+    #[test]
+    fn rejects_bad_tokens() {
+        assert!("48 GG".parse::<Signature>().is_err());
+        assert!("48 123".parse::<Signature>().is_err());
+        assert!("?? ??".parse::<Signature>().is_err());
+        assert!("".parse::<Signature>().is_err());
+    }
+
+    #[test]
+    fn finds_matches() {
+        let image = image();
+        let signature: Signature = "48 8B 05 ?? ?? ?? ??".parse().unwrap();
+        assert_eq!(signature.find(&image.bytes), Some(0x10));
+        assert_eq!(signature.find_all(&image.bytes), vec![0x10, 0x30]);
+
+        let leading: Signature = "?? 8B 05 F0".parse().unwrap();
+        assert_eq!(leading.find_all(&image.bytes), vec![0x30]);
+
+        let missing: Signature = "48 8B 0D".parse().unwrap();
+        assert_eq!(missing.find(&image.bytes), None);
+        assert_eq!(missing.find(&[0x48]), None);
+    }
+
+    #[test]
+    fn resolves_rip_relative_operands() {
+        let image = image();
+        assert_eq!(image.resolve_rip(0x10, 3, 7), Some(BASE + 0x17 + 0x120));
+        assert_eq!(image.resolve_rip(0x30, 3, 7), Some(BASE + 0x37 - 0x10));
+        assert_eq!(image.resolve_rip(0x3E, 3, 7), None);
+
+        let pointer = StaticPointer {
+            signature: "48 8B 05 20 01".parse().unwrap(),
+            operand_offset: 3,
+            instruction_len: 7,
+        };
+        assert_eq!(pointer.resolve(&image), Some(BASE + 0x137));
+    }
+
+    #[test]
+    fn scans_a_dump_file() {
+        let path =
+            std::env::temp_dir().join(format!("mcbe-fisher-dump-{}.bin", std::process::id()));
+        fs::write(&path, image().bytes).unwrap();
+        let image = ModuleImage::from_file(&path, BASE);
+        let _ = fs::remove_file(&path);
+
+        let image = image.unwrap();
+        let signature: Signature = "48 8B 05".parse().unwrap();
+        assert_eq!(signature.find_all(&image.bytes), vec![0x10, 0x30]);
+        assert_eq!(image.resolve_rip(0x10, 3, 7), Some(BASE + 0x137));
+    }
+}
@@ -0,0 +1,299 @@
+use crate::fisher::{Fisher, FisherConfig};
+use crate::memory::{find_processes, MemoryReader, PointerTrace, ProcessMemory};
+use crate::offsets::{self, OffsetDatabase};
+use crate::trace::TraceRecorder;
+use parking_lot::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(2);
+const MAX_NOT_READY_RETRIES: u32 = 30;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SessionState {
+    Fishing,
+    ProcessExited,
+    WaitingForGame,
+    Stopped,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum RunEnd {
+    Retry,
+    Reattach,
+    Stop,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct SessionOptions {
+    pub config: FisherConfig,
+    pub resume: bool,
+    pub record: bool,
+}
+
+pub struct Supervisor {
+    process_name: String,
+    offsets: OffsetDatabase,
+    options: SessionOptions,
+    fisher: Mutex<Option<Arc<Fisher<MemoryReader>>>>,
+    state: Mutex<SessionState>,
+    should_stop: AtomicBool,
+    last_error: Mutex<Option<String>>,
+}
+
+impl Supervisor {
+    pub fn new(process_name: String, offsets: OffsetDatabase, options: SessionOptions) -> Self {
+        Supervisor {
+            process_name,
+            offsets,
+            options,
+            fisher: Mutex::new(None),
+            state: Mutex::new(SessionState::Fishing),
+            should_stop: AtomicBool::new(false),
+            last_error: Mutex::new(None),
+        }
+    }
+
+    pub fn run(&self, pid: u32, memory: Arc<MemoryReader>, stop_sender: Sender<()>) {
+        let mut pid = pid;
+        let mut memory = Some(memory);
+        let mut config = self.options.config;
+        let mut reconnected = false;
+        let mut retries = 0;
+        let trace = if self.options.record {
+            self.create_trace()
+        } else {
+            None
+        };
+
+        while !self.should_stop.load(Ordering::Relaxed) {
+            let current = match memory.take() {
+                Some(memory) => memory,
+                None => match MemoryReader::new(pid) {
+                    Ok(memory) => Arc::new(memory),
+                    Err(e) => {
+                        println!("failed to open game process: {}", e);
+                        thread::sleep(RECONNECT_INTERVAL);
+                        if !self.is_listed(pid) {
+                            match self.wait_for_game(pid) {
+                                Some(new_pid) => pid = new_pid,
+                                None => break,
+                            }
+                        }
+                        continue;
+                    }
+                },
+            };
+
+            let fisher = Arc::new(Fisher::new(current, self.offsets.clone(), trace.clone()));
+            *self.fisher.lock() = Some(fisher.clone());
+            if self.should_stop.load(Ordering::Relaxed) {
+                break;
+            }
+            self.set_state(SessionState::Fishing);
+
+            let casts = fisher.run(&config);
+            if let Some(max) = config.max_casts.as_mut() {
+                *max -= casts;
+            }
+
+            match self.run_end(&fisher, casts, &config, reconnected, &mut retries) {
+                RunEnd::Retry => {
+                    thread::sleep(RECONNECT_INTERVAL);
+                    continue;
+                }
+                RunEnd::Stop => break,
+                RunEnd::Reattach => {}
+            }
+
+            match self.wait_for_game(pid) {
+                Some(new_pid) => {
+                    println!("reattaching to game process {}", new_pid);
+                    pid = new_pid;
+                    reconnected = true;
+                    retries = 0;
+                }
+                None => break,
+            }
+        }
+
+        self.set_state(SessionState::Stopped);
+        let _ = stop_sender.send(());
+    }
+
+    pub fn stop(&self) {
+        self.should_stop.store(true, Ordering::Relaxed);
+        if let Some(fisher) = self.fisher.lock().as_ref() {
+            fisher.stop();
+        }
+    }
+
+    pub fn state(&self) -> SessionState {
+        *self.state.lock()
+    }
+
+    pub fn last_error(&self) -> Option<String> {
+        self.last_error.lock().clone()
+    }
+
+    pub fn last_trace(&self) -> Option<Arc<PointerTrace>> {
+        self.fisher.lock().as_ref()?.last_trace()
+    }
+
+    fn run_end<M: ProcessMemory>(
+        &self,
+        fisher: &Fisher<M>,
+        casts: i32,
+        config: &FisherConfig,
+        reconnected: bool,
+        retries: &mut u32,
+    ) -> RunEnd {
+        if !fisher.process_exited() {
+            if reconnected
+                && casts == 0
+                && fisher.last_error().is_some()
+                && *retries < MAX_NOT_READY_RETRIES
+            {
+                *retries += 1;
+                println!("game is not ready yet, retrying...");
+                return RunEnd::Retry;
+            }
+            *self.last_error.lock() = fisher.last_error();
+            return RunEnd::Stop;
+        }
+
+        self.set_state(SessionState::ProcessExited);
+        if !self.options.resume {
+            *self.last_error.lock() = Some("game process exited".to_string());
+            return RunEnd::Stop;
+        }
+        if config.max_casts.is_some_and(|max| max <= 0) {
+            return RunEnd::Stop;
+        }
+        RunEnd::Reattach
+    }
+
+    fn set_state(&self, state: SessionState) {
+        *self.state.lock() = state;
+    }
+
+    fn create_trace(&self) -> Option<Arc<TraceRecorder>> {
+        let path = offsets::exe_dir().join("traces").join(format!(
+            "trace-{}.txt",
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs()
+        ));
+        match TraceRecorder::create(&path) {
+            Ok(trace) => {
+                println!("recording trace to {}", path.display());
+                Some(Arc::new(trace))
+            }
+            Err(e) => {
+                println!("failed to create {}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+
+    fn is_listed(&self, pid: u32) -> bool {
+        find_processes(&self.process_name)
+            .iter()
+            .any(|process| process.pid == pid)
+    }
+
+    fn wait_for_game(&self, exited_pid: u32) -> Option<u32> {
+        self.set_state(SessionState::WaitingForGame);
+        println!("waiting for {} to start again...", self.process_name);
+
+        let exited_at = SystemTime::now();
+        let mut running: Vec<u32> = find_processes(&self.process_name)
+            .iter()
+            .map(|process| process.pid)
+            .collect();
+        running.push(exited_pid);
+
+        while !self.should_stop.load(Ordering::Relaxed) {
+            let process = find_processes(&self.process_name)
+                .into_iter()
+                .find(|process| {
+                    !running.contains(&process.pid)
+                        || process.start_time.is_some_and(|start| start > exited_at)
+                });
+            if let Some(process) = process {
+                return Some(process.pid);
+            }
+            thread::sleep(RECONNECT_INTERVAL);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::FakeMemory;
+
+    fn session(resume: bool) -> Supervisor {
+        Supervisor::new(
+            crate::memory::DEFAULT_PROCESS.to_string(),
+            OffsetDatabase::default(),
+            SessionOptions {
+                config: FisherConfig::default(),
+                resume,
+                record: false,
+            },
+        )
+    }
+
+    fn fisher(memory: FakeMemory) -> Fisher<FakeMemory> {
+        Fisher::new(Arc::new(memory), OffsetDatabase::default(), None)
+    }
+
+    #[test]
+    fn resumes_when_the_game_exits_while_resolving() {
+        let memory = FakeMemory::new();
+        memory.exit();
+        let fisher = fisher(memory);
+        let config = FisherConfig::default();
+        assert_eq!(fisher.run(&config), 0);
+        assert!(fisher.process_exited());
+
+        let supervisor = session(true);
+        let end = supervisor.run_end(&fisher, 0, &config, false, &mut 0);
+        assert_eq!(end, RunEnd::Reattach);
+        assert_eq!(supervisor.state(), SessionState::ProcessExited);
+        assert_eq!(supervisor.last_error(), None);
+
+        let supervisor = session(false);
+        let end = supervisor.run_end(&fisher, 0, &config, false, &mut 0);
+        assert_eq!(end, RunEnd::Stop);
+        assert_eq!(
+            supervisor.last_error().as_deref(),
+            Some("game process exited")
+        );
+    }
+
+    #[test]
+    fn gives_up_on_offsets_that_never_resolve() {
+        let fisher = fisher(FakeMemory::new());
+        let config = FisherConfig::default();
+        assert_eq!(fisher.run(&config), 0);
+        assert!(!fisher.process_exited());
+
+        let supervisor = session(true);
+        let end = supervisor.run_end(&fisher, 0, &config, false, &mut 0);
+        assert_eq!(end, RunEnd::Stop);
+        assert!(supervisor.last_error().is_some());
+
+        let supervisor = session(true);
+        let mut retries = 0;
+        while supervisor.run_end(&fisher, 0, &config, true, &mut retries) == RunEnd::Retry {}
+        assert_eq!(retries, MAX_NOT_READY_RETRIES);
+        assert!(supervisor.last_error().is_some());
+    }
+}
@@ -0,0 +1,377 @@
+use crate::binary::invalid_data;
+use crate::detector::{DetectorSettings, Sample};
+use parking_lot::Mutex;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+const HEADER: &str = "# mcbe-fisher-rs trace";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TraceEvent {
+    Cast,
+    Sample(u32),
+    Reel,
+    Timeout,
+    Bite,
+}
+
+pub struct TraceRecorder {
+    start: Instant,
+    writer: Mutex<BufWriter<File>>,
+}
+
+pub struct Trace {
+    pub events: Vec<(Duration, TraceEvent)>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct CastRecord {
+    pub start: Duration,
+    pub samples: Vec<Sample>,
+    pub bite: Option<Duration>,
+    pub reel: Option<Duration>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Outcome {
+    Hit(Duration),
+    Late(Duration),
+    Early(Duration),
+    Missed,
+    FalseAlarm,
+    Quiet,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ReplaySummary {
+    pub casts: usize,
+    pub hits: usize,
+    pub late: usize,
+    pub early: usize,
+    pub missed: usize,
+    pub false_alarms: usize,
+    pub quiet: usize,
+    pub latency: Duration,
+}
+
+impl TraceRecorder {
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        if let Some(dir) = path.as_ref().parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "{}", HEADER)?;
+        Ok(TraceRecorder {
+            start: Instant::now(),
+            writer: Mutex::new(writer),
+        })
+    }
+
+    pub fn record(&self, event: TraceEvent) {
+        let time = self.start.elapsed().as_millis();
+        let mut writer = self.writer.lock();
+        let result = match event {
+            TraceEvent::Cast => writeln!(writer, "cast {}", time),
+            TraceEvent::Sample(value) => writeln!(writer, "sample {} {}", time, value),
+            TraceEvent::Reel => writeln!(writer, "reel {}", time),
+            TraceEvent::Timeout => writeln!(writer, "timeout {}", time),
+            TraceEvent::Bite => writeln!(writer, "bite {}", time),
+        };
+        if let Err(e) = result.and_then(|_| writer.flush()) {
+            println!("failed to write trace: {}", e);
+        }
+    }
+}
+
+impl Trace {
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut events = Vec::new();
+
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let invalid = || invalid_data(format!("invalid trace line {}: {}", index + 1, line));
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let time = fields
+                .get(1)
+                .and_then(|time| time.parse().ok())
+                .map(Duration::from_millis)
+                .ok_or_else(invalid)?;
+            let event = match (fields[0], fields.get(2)) {
+                ("cast", None) => TraceEvent::Cast,
+                ("sample", Some(value)) => {
+                    TraceEvent::Sample(value.parse().map_err(|_| invalid())?)
+                }
+                ("reel", None) => TraceEvent::Reel,
+                ("timeout", None) => TraceEvent::Timeout,
+                ("bite", None) => TraceEvent::Bite,
+                _ => return Err(invalid()),
+            };
+            events.push((time, event));
+        }
+
+        events.sort_by_key(|(time, _)| *time);
+        Ok(Trace { events })
+    }
+
+    pub fn casts(&self) -> Vec<CastRecord> {
+        let mut casts: Vec<CastRecord> = Vec::new();
+        for &(time, event) in &self.events {
+            if event == TraceEvent::Cast {
+                casts.push(CastRecord {
+                    start: time,
+                    ..Default::default()
+                });
+                continue;
+            }
+
+            let cast = match casts.last_mut() {
+                Some(cast) => cast,
+                None => continue,
+            };
+            match event {
+                TraceEvent::Sample(value) => cast.samples.push(Sample {
+                    time: time - cast.start,
+                    value,
+                }),
+                TraceEvent::Bite => {
+                    cast.bite.get_or_insert(time - cast.start);
+                }
+                TraceEvent::Reel => {
+                    cast.reel.get_or_insert(time - cast.start);
+                }
+                TraceEvent::Cast | TraceEvent::Timeout => {}
+            }
+        }
+        casts
+    }
+}
+
+impl CastRecord {
+    pub fn detect(&self, settings: &DetectorSettings) -> Option<Duration> {
+        let mut detector = settings.build();
+        let mut waiting = false;
+        for &sample in &self.samples {
+            if !waiting {
+                waiting = sample.value > 0;
+            } else if detector.update(sample) {
+                return Some(sample.time);
+            }
+        }
+        None
+    }
+
+    pub fn judge(&self, reel: Option<Duration>, tolerance: Duration) -> Outcome {
+        match (self.bite, reel) {
+            (Some(bite), Some(reel)) if reel < bite => Outcome::Early(bite - reel),
+            (Some(bite), Some(reel)) if reel - bite <= tolerance => Outcome::Hit(reel - bite),
+            (Some(bite), Some(reel)) => Outcome::Late(reel - bite),
+            (Some(_), None) => Outcome::Missed,
+            (None, Some(_)) => Outcome::FalseAlarm,
+            (None, None) => Outcome::Quiet,
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Outcome::Hit(latency) => write!(f, "hit, {} ms after the bite", latency.as_millis()),
+            Outcome::Late(latency) => write!(f, "late, {} ms after the bite", latency.as_millis()),
+            Outcome::Early(lead) => write!(f, "early, {} ms before the bite", lead.as_millis()),
+            Outcome::Missed => write!(f, "missed the bite"),
+            Outcome::FalseAlarm => write!(f, "reeled without a bite"),
+            Outcome::Quiet => write!(f, "no bite, no reel"),
+        }
+    }
+}
+
+impl ReplaySummary {
+    pub fn add(&mut self, outcome: Outcome) {
+        self.casts += 1;
+        match outcome {
+            Outcome::Hit(latency) => {
+                self.hits += 1;
+                self.latency += latency;
+            }
+            Outcome::Late(_) => self.late += 1,
+            Outcome::Early(_) => self.early += 1,
+            Outcome::Missed => self.missed += 1,
+            Outcome::FalseAlarm => self.false_alarms += 1,
+            Outcome::Quiet => self.quiet += 1,
+        }
+    }
+
+    pub fn mean_latency(&self) -> Option<Duration> {
+        (self.hits > 0).then(|| self.latency / self.hits as u32)
+    }
+
+    pub fn precision(&self) -> f32 {
+        let reels = self.hits + self.late + self.early + self.false_alarms;
+        ratio(self.hits, reels)
+    }
+
+    pub fn recall(&self) -> f32 {
+        let bites = self.hits + self.late + self.early + self.missed;
+        ratio(self.hits, bites)
+    }
+
+    pub fn f1(&self) -> f32 {
+        let (precision, recall) = (self.precision(), self.recall());
+        if precision + recall > 0.0 {
+            2.0 * precision * recall / (precision + recall)
+        } else {
+            0.0
+        }
+    }
+}
+
+impl fmt::Display for ReplaySummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} casts: {} hits, {} late, {} early, {} missed, {} false alarms",
+            self.casts, self.hits, self.late, self.early, self.missed, self.false_alarms
+        )?;
+        if let Some(latency) = self.mean_latency() {
+            write!(f, ", mean latency {} ms", latency.as_millis())?;
+        }
+        Ok(())
+    }
+}
+
+fn ratio(count: usize, total: usize) -> f32 {
+    if total > 0 {
+        count as f32 / total as f32
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/labelled.txt");
+    const TOLERANCE: Duration = Duration::from_millis(1000);
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn loads_a_labelled_trace() {
+        let trace = Trace::load(FIXTURE).unwrap();
+        assert_eq!(trace.events.len(), 43);
+        assert_eq!(trace.events[0], (ms(0), TraceEvent::Cast));
+        assert_eq!(trace.events[7], (ms(320), TraceEvent::Bite));
+        assert_eq!(trace.events.last(), Some(&(ms(6360), TraceEvent::Reel)));
+    }
+
+    #[test]
+    fn rejects_malformed_lines() {
+        let path =
+            std::env::temp_dir().join(format!("mcbe-fisher-trace-{}.txt", std::process::id()));
+        for line in ["sample 10", "cast", "bite soon", "splash 10"] {
+            fs::write(&path, format!("{}\ncast 0\n{}\n", HEADER, line)).unwrap();
+            let error = Trace::load(&path).err().unwrap();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+            assert!(error.to_string().contains("line 3"));
+        }
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn splits_casts() {
+        let casts = Trace::load(FIXTURE).unwrap().casts();
+        assert_eq!(casts.len(), 4);
+        assert_eq!(casts[0].start, ms(0));
+        assert_eq!(casts[0].samples.len(), 10);
+        assert_eq!(casts[0].bite, Some(ms(320)));
+        assert_eq!(casts[0].reel, Some(ms(520)));
+
+        assert_eq!(casts[1].start, ms(2000));
+        assert_eq!(casts[1].samples[0].time, ms(50));
+        assert_eq!((casts[1].bite, casts[1].reel), (None, None));
+        assert_eq!(casts[2].bite, Some(ms(280)));
+        assert_eq!((casts[3].bite, casts[3].reel), (None, Some(ms(360))));
+    }
+
+    #[test]
+    fn judges_the_default_detector() {
+        let settings = DetectorSettings::default();
+        let casts = Trace::load(FIXTURE).unwrap().casts();
+        let outcomes: Vec<Outcome> = casts
+            .iter()
+            .map(|cast| cast.judge(cast.detect(&settings), TOLERANCE))
+            .collect();
+        assert_eq!(
+            outcomes,
+            vec![
+                Outcome::Hit(ms(180)),
+                Outcome::Quiet,
+                Outcome::Missed,
+                Outcome::FalseAlarm
+            ]
+        );
+    }
+
+    #[test]
+    fn judges_reel_timing() {
+        let cast = CastRecord {
+            bite: Some(ms(500)),
+            ..Default::default()
+        };
+        assert_eq!(
+            cast.judge(Some(ms(400)), TOLERANCE),
+            Outcome::Early(ms(100))
+        );
+        assert_eq!(
+            cast.judge(Some(ms(1500)), TOLERANCE),
+            Outcome::Hit(ms(1000))
+        );
+        assert_eq!(
+            cast.judge(Some(ms(1600)), TOLERANCE),
+            Outcome::Late(ms(1100))
+        );
+        assert_eq!(cast.judge(None, TOLERANCE), Outcome::Missed);
+    }
+
+    #[test]
+    fn summarises_outcomes() {
+        let mut summary = ReplaySummary::default();
+        assert_eq!(
+            (summary.precision(), summary.recall(), summary.f1()),
+            (0.0, 0.0, 0.0)
+        );
+        assert_eq!(summary.mean_latency(), None);
+
+        for outcome in [
+            Outcome::Hit(ms(100)),
+            Outcome::Hit(ms(300)),
+            Outcome::Late(ms(1500)),
+            Outcome::Missed,
+            Outcome::FalseAlarm,
+            Outcome::Quiet,
+        ] {
+            summary.add(outcome);
+        }
+        assert_eq!(summary.casts, 6);
+        assert_eq!(summary.mean_latency(), Some(ms(200)));
+        assert_eq!(summary.precision(), 0.5);
+        assert_eq!(summary.recall(), 0.5);
+        assert_eq!(summary.f1(), 0.5);
+        assert_eq!(
+            summary.to_string(),
+            "6 casts: 2 hits, 1 late, 0 early, 1 missed, 1 false alarms, mean latency 200 ms"
+        );
+    }
+}
@@ -0,0 +1,186 @@
+use crate::detector::{
+    ChangePointConfig, DetectorKind, DetectorSettings, EdgeConfig, MovingAverageConfig,
+    PeakFallConfig,
+};
+use crate::trace::{CastRecord, ReplaySummary};
+use std::cmp::Ordering;
+use std::time::Duration;
+
+const PEAK_FALL_THRESHOLDS: [u32; 6] = [0, 1, 2, 3, 4, 5];
+const PEAK_FALL_STABILITY: [u32; 5] = [1, 2, 3, 4, 5];
+const MIN_PEAKS: [u32; 5] = [1, 3, 5, 8, 12];
+const PEAK_FALL_FALLING: [u32; 5] = [0, 1, 2, 3, 4];
+const EDGE_MIN_RATES: [f32; 6] = [2.0, 5.0, 10.0, 20.0, 40.0, 80.0];
+const EDGE_CONFIRMS: [u32; 4] = [1, 2, 3, 4];
+const MOVING_AVERAGE_WINDOWS: [usize; 6] = [1, 2, 3, 4, 6, 8];
+const MOVING_AVERAGE_RISES: [f32; 5] = [1.0, 2.0, 5.0, 10.0, 20.0];
+const MOVING_AVERAGE_FALLS: [f32; 5] = [0.5, 1.0, 2.0, 4.0, 8.0];
+const CHANGE_POINT_DRIFTS: [f32; 5] = [0.0, 0.25, 0.5, 1.0, 2.0];
+const CHANGE_POINT_THRESHOLDS: [f32; 7] = [1.0, 2.0, 3.0, 4.0, 6.0, 8.0, 12.0];
+
+pub struct TuneResult {
+    pub settings: DetectorSettings,
+    pub summary: ReplaySummary,
+}
+
+pub fn candidates(kind: DetectorKind) -> Vec<DetectorSettings> {
+    let base = DetectorSettings {
+        kind,
+        ..Default::default()
+    };
+    let mut candidates = Vec::new();
+
+    match kind {
+        DetectorKind::PeakFall => {
+            for threshold in PEAK_FALL_THRESHOLDS {
+                for stability in PEAK_FALL_STABILITY {
+                    for min_peak in MIN_PEAKS {
+                        for falling in PEAK_FALL_FALLING {
+                            candidates.push(DetectorSettings {
+                                peak_fall: PeakFallConfig {
+                                    threshold,
+                                    stability,
+                                    min_peak,
+                                    falling,
+                                },
+                                ..base
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        DetectorKind::Edge => {
+            for min_rate in EDGE_MIN_RATES {
+                for confirm in EDGE_CONFIRMS {
+                    for min_peak in MIN_PEAKS {
+                        candidates.push(DetectorSettings {
+                            edge: EdgeConfig {
+                                min_rate,
+                                confirm,
+                                min_peak,
+                            },
+                            ..base
+                        });
+                    }
+                }
+            }
+        }
+        DetectorKind::MovingAverage => {
+            for window in MOVING_AVERAGE_WINDOWS {
+                for rise in MOVING_AVERAGE_RISES {
+                    for fall in MOVING_AVERAGE_FALLS {
+                        candidates.push(DetectorSettings {
+                            moving_average: MovingAverageConfig { window, rise, fall },
+                            ..base
+                        });
+                    }
+                }
+            }
+        }
+        DetectorKind::ChangePoint => {
+            for drift in CHANGE_POINT_DRIFTS {
+                for threshold in CHANGE_POINT_THRESHOLDS {
+                    for min_peak in MIN_PEAKS {
+                        candidates.push(DetectorSettings {
+                            change_point: ChangePointConfig {
+                                drift,
+                                threshold,
+                                min_peak,
+                            },
+                            ..base
+                        });
+                    }
+                }
+            }
+        }
+    }
+    candidates
+}
+
+pub fn evaluate(
+    casts: &[CastRecord],
+    settings: &DetectorSettings,
+    tolerance: Duration,
+) -> ReplaySummary {
+    let mut summary = ReplaySummary::default();
+    for cast in casts {
+        summary.add(cast.judge(cast.detect(settings), tolerance));
+    }
+    summary
+}
+
+pub fn tune(casts: &[CastRecord], kinds: &[DetectorKind], tolerance: Duration) -> Vec<TuneResult> {
+    let mut results: Vec<TuneResult> = kinds
+        .iter()
+        .flat_map(|&kind| candidates(kind))
+        .map(|settings| TuneResult {
+            summary: evaluate(casts, &settings, tolerance),
+            settings,
+        })
+        .collect();
+
+    results.sort_by(|a, b| compare(&b.summary, &a.summary));
+    results
+}
+
+fn compare(a: &ReplaySummary, b: &ReplaySummary) -> Ordering {
+    a.f1()
+        .total_cmp(&b.f1())
+        .then_with(|| a.recall().total_cmp(&b.recall()))
+        .then_with(|| {
+            let latency = |summary: &ReplaySummary| summary.mean_latency().unwrap_or(Duration::MAX);
+            latency(b).cmp(&latency(a))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detector::Sample;
+
+    const TOLERANCE: Duration = Duration::from_millis(1000);
+
+    fn cast(values: &[u32], bite: Option<u64>) -> CastRecord {
+        CastRecord {
+            samples: values
+                .iter()
+                .enumerate()
+                .map(|(index, &value)| Sample {
+                    time: Duration::from_millis(50 * (index as u64 + 1)),
+                    value,
+                })
+                .collect(),
+            bite: bite.map(Duration::from_millis),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn ranks_the_best_setting_first() {
+        let casts = [
+            cast(
+                &[1, 10, 20, 20, 20, 20, 20, 20, 20, 20, 15, 10, 5],
+                Some(520),
+            ),
+            cast(&[1, 10, 20, 20, 17, 20, 20, 20], None),
+        ];
+        let results = tune(&casts, &[DetectorKind::Edge], TOLERANCE);
+        assert_eq!(results.len(), candidates(DetectorKind::Edge).len());
+
+        let best = &results[0];
+        assert_eq!(best.settings.kind, DetectorKind::Edge);
+        assert_eq!(
+            (best.settings.edge.min_rate, best.settings.edge.confirm),
+            (80.0, 1)
+        );
+        assert_eq!((best.summary.hits, best.summary.quiet), (1, 1));
+        assert_eq!(best.summary.mean_latency(), Some(Duration::from_millis(30)));
+
+        let default = evaluate(&casts, &DetectorSettings::default(), TOLERANCE);
+        assert_eq!(compare(&best.summary, &default), Ordering::Greater);
+        for pair in results.windows(2) {
+            assert_ne!(compare(&pair[0].summary, &pair[1].summary), Ordering::Less);
+        }
+    }
+}
@@ -0,0 +1,420 @@
+use crate::memory::{self, Address, ProcessMemory};
+use std::fmt;
+use std::str::FromStr;
+
+const PAGE_SIZE: usize = 0x1000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ValueType {
+    U8,
+    U16,
+    U32,
+    U64,
+    I32,
+    I64,
+    F32,
+    F64,
+}
+
+impl ValueType {
+    pub const ALL: [ValueType; 8] = [
+        ValueType::U8,
+        ValueType::U16,
+        ValueType::U32,
+        ValueType::U64,
+        ValueType::I32,
+        ValueType::I64,
+        ValueType::F32,
+        ValueType::F64,
+    ];
+
+    pub fn size(self) -> usize {
+        match self {
+            ValueType::U8 => 1,
+            ValueType::U16 => 2,
+            ValueType::U32 | ValueType::I32 | ValueType::F32 => 4,
+            ValueType::U64 | ValueType::I64 | ValueType::F64 => 8,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ValueType::U8 => "u8",
+            ValueType::U16 => "u16",
+            ValueType::U32 => "u32",
+            ValueType::U64 => "u64",
+            ValueType::I32 => "i32",
+            ValueType::I64 => "i64",
+            ValueType::F32 => "f32",
+            ValueType::F64 => "f64",
+        }
+    }
+
+    pub fn decode(self, bytes: &[u8]) -> Value {
+        let mut raw = [0u8; 8];
+        raw[..self.size()].copy_from_slice(&bytes[..self.size()]);
+        let raw = u64::from_le_bytes(raw);
+
+        match self {
+            ValueType::U8 | ValueType::U16 | ValueType::U32 | ValueType::U64 => {
+                Value::Unsigned(raw)
+            }
+            ValueType::I32 => Value::Signed(raw as u32 as i32 as i64),
+            ValueType::I64 => Value::Signed(raw as i64),
+            ValueType::F32 => Value::Float(f32::from_bits(raw as u32) as f64),
+            ValueType::F64 => Value::Float(f64::from_bits(raw)),
+        }
+    }
+
+    pub fn parse_value(self, value: &str) -> Result<Value, String> {
+        let invalid = || format!("invalid {} value: {}", self.name(), value);
+        let value = value.trim();
+
+        match self {
+            ValueType::U8 | ValueType::U16 | ValueType::U32 | ValueType::U64 => {
+                let parsed = match value.strip_prefix("0x") {
+                    Some(hex) => u64::from_str_radix(hex, 16),
+                    None => value.parse(),
+                }
+                .map_err(|_| invalid())?;
+                if self.size() < 8 && parsed >> (self.size() * 8) != 0 {
+                    return Err(invalid());
+                }
+                Ok(Value::Unsigned(parsed))
+            }
+            ValueType::I32 => value
+                .parse::<i32>()
+                .map(|v| Value::Signed(v as i64))
+                .map_err(|_| invalid()),
+            ValueType::I64 => value.parse().map(Value::Signed).map_err(|_| invalid()),
+            ValueType::F32 => value
+                .parse::<f32>()
+                .map(|v| Value::Float(v as f64))
+                .map_err(|_| invalid()),
+            ValueType::F64 => value.parse().map(Value::Float).map_err(|_| invalid()),
+        }
+    }
+}
+
+impl FromStr for ValueType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ValueType::ALL
+            .into_iter()
+            .find(|value_type| value_type.name() == s)
+            .ok_or_else(|| format!("unknown value type: {}", s))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub enum Value {
+    Unsigned(u64),
+    Signed(i64),
+    Float(f64),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Unsigned(value) => write!(f, "{}", value),
+            Value::Signed(value) => write!(f, "{}", value),
+            Value::Float(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Filter {
+    Exact(Value),
+    Changed,
+    Unchanged,
+    Increased,
+    Decreased,
+}
+
+impl Filter {
+    pub fn parse(filter: &str, value_type: ValueType) -> Result<Self, String> {
+        let mut parts = filter.split_whitespace();
+        match (parts.next(), parts.next()) {
+            (Some("exact"), Some(value)) => Ok(Filter::Exact(value_type.parse_value(value)?)),
+            (Some("changed"), None) => Ok(Filter::Changed),
+            (Some("unchanged"), None) => Ok(Filter::Unchanged),
+            (Some("increased"), None) => Ok(Filter::Increased),
+            (Some("decreased"), None) => Ok(Filter::Decreased),
+            _ => Err(format!("unknown filter: {}", filter)),
+        }
+    }
+
+    fn matches(&self, previous: Value, current: Value) -> bool {
+        match self {
+            Filter::Exact(value) => current == *value,
+            Filter::Changed => current != previous,
+            Filter::Unchanged => current == previous,
+            Filter::Increased => current > previous,
+            Filter::Decreased => current < previous,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Candidate {
+    pub address: Address,
+    pub value: Value,
+}
+
+enum Results {
+    Empty,
+    Snapshot(Vec<(Address, Vec<u8>)>),
+    Candidates(Vec<Candidate>),
+}
+
+pub struct ValueScanner {
+    pub value_type: ValueType,
+    results: Results,
+}
+
+impl ValueScanner {
+    pub fn new(value_type: ValueType) -> Self {
+        ValueScanner {
+            value_type,
+            results: Results::Empty,
+        }
+    }
+
+    pub fn is_started(&self) -> bool {
+        !matches!(self.results, Results::Empty)
+    }
+
+    pub fn candidates(&self) -> &[Candidate] {
+        match &self.results {
+            Results::Candidates(candidates) => candidates,
+            _ => &[],
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        match &self.results {
+            Results::Empty => "no scan yet".to_string(),
+            Results::Snapshot(snapshot) => format!(
+                "recorded 0x{:X} bytes, filter them with a next scan",
+                snapshot.iter().map(|(_, bytes)| bytes.len()).sum::<usize>()
+            ),
+            Results::Candidates(candidates) => format!("{} candidates", candidates.len()),
+        }
+    }
+
+    pub fn first_scan<M: ProcessMemory>(
+        &mut self,
+        memory: &M,
+        exact: Option<Value>,
+    ) -> memory::Result<()> {
+        let ranges = memory.readable_ranges()?;
+        let value_type = self.value_type;
+        let size = value_type.size();
+
+        self.results = match exact {
+            Some(exact) => {
+                let mut candidates = Vec::new();
+                for range in ranges {
+                    memory::read_chunks(memory, range, |address, bytes| {
+                        for (index, value) in bytes.chunks_exact(size).enumerate() {
+                            let value = value_type.decode(value);
+                            if value == exact {
+                                candidates.push(Candidate {
+                                    address: address + index * size,
+                                    value,
+                                });
+                            }
+                        }
+                    });
+                }
+                Results::Candidates(candidates)
+            }
+            None => {
+                // an unknown value keeps a copy of every readable region until the next scan,
+                // which for the game is several gigabytes
+                let mut snapshot = Vec::new();
+                for range in ranges {
+                    memory::read_chunks(memory, range, |address, bytes| {
+                        snapshot.push((address, bytes.to_vec()));
+                    });
+                }
+                Results::Snapshot(snapshot)
+            }
+        };
+        Ok(())
+    }
+
+    pub fn next_scan<M: ProcessMemory>(
+        &mut self,
+        memory: &M,
+        filter: Filter,
+    ) -> memory::Result<()> {
+        let value_type = self.value_type;
+        let size = value_type.size();
+
+        match &mut self.results {
+            Results::Empty => {
+                let exact = match filter {
+                    Filter::Exact(value) => Some(value),
+                    _ => None,
+                };
+                return self.first_scan(memory, exact);
+            }
+            Results::Snapshot(snapshot) => {
+                let mut candidates = Vec::new();
+                for (base, previous) in snapshot.iter() {
+                    let current = match memory.read_bytes(*base, previous.len()) {
+                        Ok(current) => current,
+                        Err(_) => continue,
+                    };
+
+                    let pairs = previous.chunks_exact(size).zip(current.chunks_exact(size));
+                    for (index, (previous, current)) in pairs.enumerate() {
+                        let previous = value_type.decode(previous);
+                        let current = value_type.decode(current);
+                        if filter.matches(previous, current) {
+                            candidates.push(Candidate {
+                                address: base + index * size,
+                                value: current,
+                            });
+                        }
+                    }
+                }
+                self.results = Results::Candidates(candidates);
+            }
+            Results::Candidates(candidates) => {
+                let mut page = vec![0u8; PAGE_SIZE];
+                let mut loaded: Option<(Address, bool)> = None;
+
+                candidates.retain_mut(|candidate| {
+                    let page_base = candidate.address & !(PAGE_SIZE - 1);
+                    let readable = match loaded {
+                        Some((address, readable)) if address == page_base => readable,
+                        _ => {
+                            let readable = memory.read_raw(page_base, &mut page).is_ok();
+                            loaded = Some((page_base, readable));
+                            readable
+                        }
+                    };
+                    if !readable {
+                        return false;
+                    }
+
+                    let offset = candidate.address - page_base;
+                    let current = value_type.decode(&page[offset..offset + size]);
+                    let keep = filter.matches(candidate.value, current);
+                    candidate.value = current;
+                    keep
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::FakeMemory;
+
+    const PAGE: Address = 0x1000_0000;
+
+    fn memory(values: &[u32]) -> FakeMemory {
+        let memory = FakeMemory::new();
+        memory.write(PAGE, &[0u8; PAGE_SIZE]);
+        memory.write(PAGE, bytemuck::cast_slice(values));
+        memory
+    }
+
+    fn addresses(scanner: &ValueScanner) -> Vec<Address> {
+        scanner
+            .candidates()
+            .iter()
+            .map(|candidate| candidate.address - PAGE)
+            .collect()
+    }
+
+    #[test]
+    fn narrows_an_unknown_value() {
+        let memory = memory(&[5, 7, 9, 100]);
+        let mut scanner = ValueScanner::new(ValueType::U32);
+        scanner.first_scan(&memory, None).unwrap();
+        assert!(scanner.is_started());
+        assert!(scanner.candidates().is_empty());
+
+        memory.put(PAGE + 4, 8u32);
+        memory.put(PAGE + 8, 3u32);
+        scanner.next_scan(&memory, Filter::Changed).unwrap();
+        assert_eq!(addresses(&scanner), vec![4, 8]);
+
+        memory.put(PAGE + 4, 10u32);
+        scanner.next_scan(&memory, Filter::Increased).unwrap();
+        assert_eq!(addresses(&scanner), vec![4]);
+        assert_eq!(scanner.candidates()[0].value, Value::Unsigned(10));
+    }
+
+    #[test]
+    fn narrows_an_exact_value() {
+        let memory = memory(&[5, 7, 9, 100, 100]);
+        let mut scanner = ValueScanner::new(ValueType::U32);
+        scanner
+            .next_scan(&memory, Filter::Exact(Value::Unsigned(100)))
+            .unwrap();
+        assert_eq!(addresses(&scanner), vec![12, 16]);
+
+        memory.put(PAGE + 16, 101u32);
+        scanner.next_scan(&memory, Filter::Unchanged).unwrap();
+        assert_eq!(addresses(&scanner), vec![12]);
+
+        memory.put(PAGE + 12, 50u32);
+        scanner.next_scan(&memory, Filter::Decreased).unwrap();
+        assert_eq!(addresses(&scanner), vec![12]);
+        scanner
+            .next_scan(&memory, Filter::Exact(Value::Unsigned(51)))
+            .unwrap();
+        assert!(scanner.candidates().is_empty());
+    }
+
+    #[test]
+    fn compares_by_type() {
+        let memory = memory(&[1]);
+        let mut unsigned = ValueScanner::new(ValueType::U32);
+        let mut signed = ValueScanner::new(ValueType::I32);
+        unsigned
+            .first_scan(&memory, Some(Value::Unsigned(1)))
+            .unwrap();
+        signed.first_scan(&memory, Some(Value::Signed(1))).unwrap();
+
+        memory.put(PAGE, -1i32);
+        unsigned.next_scan(&memory, Filter::Increased).unwrap();
+        signed.next_scan(&memory, Filter::Decreased).unwrap();
+        assert_eq!(unsigned.candidates()[0].value, Value::Unsigned(0xFFFF_FFFF));
+        assert_eq!(signed.candidates()[0].value, Value::Signed(-1));
+
+        let float = ValueType::F32.decode(&1.5f32.to_le_bytes());
+        assert_eq!(float, Value::Float(1.5));
+        assert_eq!(ValueType::F32.parse_value("1.5"), Ok(float));
+        assert!(ValueType::U8.parse_value("256").is_err());
+        assert_eq!(
+            ValueType::U16.parse_value("0x1F"),
+            Ok(Value::Unsigned(0x1F))
+        );
+    }
+
+    #[test]
+    fn parses_filters() {
+        assert!(matches!(
+            Filter::parse("exact -3", ValueType::I32),
+            Ok(Filter::Exact(Value::Signed(-3)))
+        ));
+        assert!(matches!(
+            Filter::parse("unchanged", ValueType::U8),
+            Ok(Filter::Unchanged)
+        ));
+        assert!(Filter::parse("exact", ValueType::U8).is_err());
+        assert!(Filter::parse("changed twice", ValueType::U8).is_err());
+        assert!(Filter::parse("exact -3", ValueType::U32).is_err());
+    }
+}
@@ -4,43 +4,79 @@ use image::ImageReader;
 use std::sync::mpsc::{channel, Receiver, Sender};
 use std::sync::Arc;
 use std::thread;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 
-use crate::fisher::Fisher;
-use crate::memory::{get_pid, MemoryReader};
+use crate::detector::DetectorKind;
+use crate::diagnostics::Diagnostics;
+use crate::fisher::{self, DetectionMode, FisherConfig};
+use crate::memory::{self, find_processes, MemoryReader, ProcessInfo, ProcessMemory};
+use crate::offsets::{GameBuild, OffsetDatabase, OffsetEntry};
+use crate::supervisor::{SessionOptions, SessionState, Supervisor};
 
 pub struct FisherUi {
-    fisher: Option<Arc<Fisher>>,
-    memory: Option<Arc<MemoryReader>>,
+    supervisor: Option<Arc<Supervisor>>,
     is_fishing: bool,
     casts: i32,
     custom_casts: i32,
-    threshold: u32,
+    config: FisherConfig,
+    resume: bool,
+    record: bool,
+    process_name: String,
     fishing_thread: Option<thread::JoinHandle<()>>,
     stop_sender: Option<Sender<()>>,
     stop_receiver: Option<Receiver<()>>,
     game_running: bool,
+    processes: Vec<ProcessInfo>,
+    selected_pid: Option<u32>,
+    last_process_refresh: Option<Instant>,
     version: String,
     start_time: Option<Instant>,
     countdown: i32,
+    diagnostics: Diagnostics,
+    error: Option<String>,
+    build: Option<BuildCheck>,
+    untested_confirmed: bool,
+    timings_open: bool,
+}
+
+struct BuildCheck {
+    pid: u32,
+    version: Option<String>,
+    offsets: Option<String>,
+    tested: bool,
+    has_hook: bool,
 }
 
 impl FisherUi {
     pub fn new() -> Self {
+        let (config, error) = match load_config() {
+            Ok(config) => (config, None),
+            Err(e) => (FisherConfig::default(), Some(e)),
+        };
         Self {
-            fisher: None,
-            memory: None,
+            supervisor: None,
             is_fishing: false,
-            casts: -1,
-            custom_casts: 64,
-            threshold: 1,
+            casts: if config.max_casts.is_some() { 0 } else { -1 },
+            custom_casts: config.max_casts.unwrap_or(64),
+            config,
+            resume: true,
+            record: false,
+            process_name: memory::DEFAULT_PROCESS.to_string(),
             fishing_thread: None,
             stop_sender: None,
             stop_receiver: None,
             game_running: false,
+            processes: Vec::new(),
+            selected_pid: None,
+            last_process_refresh: None,
             version: env!("CARGO_PKG_VERSION").to_string(),
             start_time: None,
             countdown: 5,
+            diagnostics: Diagnostics::new(),
+            error,
+            build: None,
+            untested_confirmed: false,
+            timings_open: false,
         }
     }
 
@@ -48,7 +84,7 @@ impl FisherUi {
         let icon_path = format!("{}/resources/icon.png", env!("CARGO_MANIFEST_DIR"));
         let icon_data = load_icon(&icon_path);
         let mut viewport = egui::ViewportBuilder::default()
-            .with_inner_size([350.0, 490.0])
+            .with_inner_size([350.0, 880.0])
             .with_resizable(false);
 
         if let Some(icon) = icon_data {
@@ -69,30 +105,60 @@ impl FisherUi {
     }
 
     fn start_fishing(&mut self) {
-        if let Some(pid) = get_pid("Minecraft.Windows.exe") {
+        self.refresh_processes();
+        if let Some(pid) = self.selected_pid {
             self.game_running = true;
-            if self.fisher.is_none() {
-                let memory = Arc::new(MemoryReader::new(pid));
-                self.memory = Some(memory.clone());
-                let fisher = Arc::new(Fisher::new(memory));
-                self.fisher = Some(fisher.clone());
-
-                let casts = if self.casts == -1 {
-                    None
-                } else {
-                    Some(self.custom_casts)
+            if self.supervisor.is_none() {
+                let tested = self.build.as_ref().is_some_and(|build| build.tested);
+                if !tested && !self.untested_confirmed {
+                    self.untested_confirmed = true;
+                    self.error = Some(
+                        "untested game version, the offsets may not work.\npress Start Fishing again to fish anyway"
+                            .to_string(),
+                    );
+                    return;
+                }
+                self.error = None;
+                let offsets = match OffsetDatabase::load() {
+                    Ok(offsets) => offsets,
+                    Err(e) => {
+                        eprintln!("failed to load offsets: {}", e);
+                        self.error = Some(e);
+                        return;
+                    }
                 };
-                let threshold = self.threshold;
+
+                let memory = match MemoryReader::new(pid) {
+                    Ok(memory) => Arc::new(memory),
+                    Err(e) => {
+                        eprintln!("failed to open game process: {}", e);
+                        self.error = Some(format!("failed to open game process: {}", e.explain()));
+                        return;
+                    }
+                };
+                let config = self.fisher_config();
+                if let Err(e) = config.validate() {
+                    self.error = Some(e);
+                    return;
+                }
+                let options = SessionOptions {
+                    config,
+                    resume: self.resume,
+                    record: self.record,
+                };
+                let supervisor =
+                    Arc::new(Supervisor::new(self.process_name.clone(), offsets, options));
+                self.supervisor = Some(supervisor.clone());
 
                 let (tx, rx) = channel();
                 self.stop_sender = Some(tx.clone());
                 self.stop_receiver = Some(rx);
 
                 self.start_time = Some(Instant::now());
-                self.countdown = 5;
+                self.countdown = self.config.initial_delay().as_secs_f32().ceil() as i32;
 
                 self.fishing_thread = Some(thread::spawn(move || {
-                    fisher.run(casts, threshold, tx);
+                    supervisor.run(pid, memory, tx);
                 }));
             }
             self.is_fishing = true;
@@ -103,8 +169,8 @@ impl FisherUi {
     }
 
     fn stop_fishing(&mut self) {
-        if let Some(fisher) = &self.fisher {
-            fisher.stop();
+        if let Some(supervisor) = &self.supervisor {
+            supervisor.stop();
         }
 
         if let Some(handle) = self.fishing_thread.take() {
@@ -122,8 +188,7 @@ impl FisherUi {
         }
 
         self.is_fishing = false;
-        self.fisher = None;
-        self.memory = None;
+        self.supervisor = None;
         self.stop_sender = None;
         self.stop_receiver = None;
     }
@@ -131,13 +196,218 @@ impl FisherUi {
     fn check_fishing_status(&mut self) {
         if let Some(receiver) = &self.stop_receiver {
             if receiver.try_recv().is_ok() {
+                if let Some(supervisor) = &self.supervisor {
+                    self.error = supervisor.last_error();
+                }
                 self.stop_fishing();
             }
         }
     }
 
     fn check_game_status(&mut self) {
-        self.game_running = get_pid("Minecraft.Windows.exe").is_some();
+        let refresh_due = self
+            .last_process_refresh
+            .is_none_or(|last| last.elapsed() >= Duration::from_secs(1));
+        if refresh_due {
+            self.refresh_processes();
+        }
+    }
+
+    fn refresh_processes(&mut self) {
+        self.processes = find_processes(&self.process_name);
+        self.last_process_refresh = Some(Instant::now());
+
+        let selected_alive = self
+            .processes
+            .iter()
+            .any(|process| Some(process.pid) == self.selected_pid);
+        if !selected_alive {
+            self.selected_pid = self.processes.first().map(|process| process.pid);
+        }
+        self.game_running = self.selected_pid.is_some();
+
+        let checked = self.build.as_ref().map(|build| build.pid);
+        if checked != self.selected_pid {
+            self.build = self.selected_pid.map(BuildCheck::run);
+            self.untested_confirmed = false;
+        }
+    }
+
+    fn fisher_config(&self) -> FisherConfig {
+        FisherConfig {
+            max_casts: if self.casts == -1 {
+                None
+            } else {
+                Some(self.custom_casts)
+            },
+            ..self.config
+        }
+    }
+
+    fn save_config(&mut self) {
+        let path = fisher::config_path();
+        let config = self.fisher_config();
+        self.error = match config.validate().and_then(|_| config.save(&path)) {
+            Ok(()) => {
+                println!("saved settings to {}", path.display());
+                None
+            }
+            Err(e) => Some(e),
+        };
+    }
+
+    fn show_timings(&mut self, ctx: &egui::Context) {
+        let mut open = self.timings_open;
+        let mut save = false;
+        egui::Window::new("Timings")
+            .open(&mut open)
+            .resizable(false)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.add_enabled_ui(!self.is_fishing, |ui| {
+                    let config = &mut self.config;
+                    let timings = [
+                        (
+                            &mut config.initial_delay_ms,
+                            fisher::INITIAL_DELAY_RANGE,
+                            "start delay",
+                        ),
+                        (
+                            &mut config.cast_delay_ms,
+                            fisher::CAST_DELAY_RANGE,
+                            "cast delay",
+                        ),
+                        (
+                            &mut config.poll_interval_ms,
+                            fisher::POLL_INTERVAL_RANGE,
+                            "poll interval",
+                        ),
+                        (
+                            &mut config.bite_timeout_ms,
+                            fisher::BITE_TIMEOUT_RANGE,
+                            "bite timeout",
+                        ),
+                        (
+                            &mut config.recast_delay_ms,
+                            fisher::RECAST_DELAY_RANGE,
+                            "recast delay",
+                        ),
+                        (&mut config.key_hold_ms, fisher::KEY_HOLD_RANGE, "key hold"),
+                    ];
+                    for (value, range, text) in timings {
+                        slider(ui, value, range, &format!("{} (ms)", text));
+                    }
+                });
+                ui.add_space(5.0);
+                ui.label(
+                    RichText::new("Slower machines and laggy servers need longer delays")
+                        .size(12.0),
+                );
+                ui.add_space(5.0);
+                save = ui.button("Save settings").clicked();
+            });
+        self.timings_open = open;
+        if save {
+            self.save_config();
+        }
+    }
+
+    fn show_detector_settings(&mut self, ui: &mut egui::Ui) {
+        let settings = &mut self.config.detector;
+        ui.label(RichText::new("Detector:").size(16.0));
+        ui.add_space(5.0);
+        ui.horizontal(|ui| {
+            ui.add_space(10.0);
+            egui::ComboBox::from_id_source("detector")
+                .width(240.0)
+                .selected_text(settings.kind.name())
+                .show_ui(ui, |ui| {
+                    for kind in DetectorKind::ALL {
+                        ui.selectable_value(&mut settings.kind, kind, kind.name());
+                    }
+                });
+        });
+        ui.add_space(5.0);
+
+        let hint = match settings.kind {
+            DetectorKind::PeakFall => {
+                let config = &mut settings.peak_fall;
+                slider(ui, &mut config.threshold, 0..=10, "threshold");
+                slider(ui, &mut config.stability, 1..=10, "stability");
+                slider(ui, &mut config.min_peak, 0..=20, "min peak");
+                slider(ui, &mut config.falling, 0..=10, "falling");
+                "Reels in once the rod state has peaked above min peak and then fallen for more than falling samples or stayed put for stability samples, threshold is the delay before reeling the rod in"
+            }
+            DetectorKind::Edge => {
+                slider(
+                    ui,
+                    &mut settings.edge.min_rate,
+                    1.0..=100.0,
+                    "fall per second",
+                );
+                slider(ui, &mut settings.edge.confirm, 1..=10, "samples");
+                slider(ui, &mut settings.edge.min_peak, 0..=20, "min peak");
+                "Reels in when the rod state falls this fast for this many samples in a row"
+            }
+            DetectorKind::MovingAverage => {
+                let config = &mut settings.moving_average;
+                slider(ui, &mut config.window, 1..=20, "window");
+                slider(ui, &mut config.rise, 0.5..=50.0, "rise");
+                slider(ui, &mut config.fall, 0.5..=50.0, "fall");
+                "Arms once the averaged rod state rises by rise, and reels in when it falls by fall from its peak"
+            }
+            DetectorKind::ChangePoint => {
+                slider(ui, &mut settings.change_point.drift, 0.0..=5.0, "drift");
+                slider(
+                    ui,
+                    &mut settings.change_point.threshold,
+                    0.5..=20.0,
+                    "threshold",
+                );
+                slider(ui, &mut settings.change_point.min_peak, 0..=20, "min peak");
+                "Reels in when the rod state steps down from its usual rate for long enough to pass the threshold"
+            }
+        };
+        ui.add_space(5.0);
+        ui.horizontal(|ui| {
+            ui.add_space(10.0);
+            ui.add(egui::Label::new(RichText::new(hint).size(12.0)).wrap());
+        });
+    }
+
+    fn show_build(&self, ui: &mut egui::Ui) {
+        let build = match &self.build {
+            Some(build) => build,
+            None => {
+                ui.label(RichText::new("start the game to check its version").size(14.0));
+                return;
+            }
+        };
+
+        let version = match &build.version {
+            Some(version) => format!("mcbe v{}", version),
+            None => "unknown mcbe version".to_string(),
+        };
+        match &build.offsets {
+            Some(offsets) if build.tested => {
+                ui.label(RichText::new(format!("{}, offsets '{}'", version, offsets)).size(14.0));
+            }
+            Some(offsets) => {
+                ui.label(
+                    RichText::new(format!("untested game version: {}", version))
+                        .size(14.0)
+                        .color(Color32::from_rgb(210, 153, 34)),
+                );
+                ui.label(RichText::new(format!("using fallback offsets '{}'", offsets)).size(12.0));
+            }
+            None => {
+                ui.label(
+                    RichText::new(format!("no offsets for {}", version))
+                        .size(14.0)
+                        .color(Color32::from_rgb(248, 81, 73)),
+                );
+            }
+        }
     }
 
     fn create_dark_visuals(&self) -> egui::Visuals {
@@ -158,6 +428,13 @@ impl eframe::App for FisherUi {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         self.check_fishing_status();
         self.check_game_status();
+        if let Some(trace) = self
+            .supervisor
+            .as_ref()
+            .and_then(|supervisor| supervisor.last_trace())
+        {
+            self.diagnostics.set_trace(trace);
+        }
 
         ctx.set_visuals(self.create_dark_visuals());
 
@@ -167,16 +444,25 @@ impl eframe::App for FisherUi {
                 ui.heading(RichText::new("mcbe-fisher-rs").size(28.0));
                 ui.add_space(15.0);
                 ui.label(RichText::new("made by z1xus <3").size(16.0));
-                ui.label(RichText::new("tested on mcbe v1.21.2 build 25836796").size(14.0));
+                self.show_build(ui);
                 ui.add_space(25.0);
 
-                let button_text = if !self.game_running {
+                let waiting = self.supervisor.as_ref().is_some_and(|supervisor| {
+                    matches!(
+                        supervisor.state(),
+                        SessionState::ProcessExited | SessionState::WaitingForGame
+                    )
+                });
+                let button_text = if !self.game_running && !self.is_fishing {
                     RichText::new("Start your game").size(18.0)
+                } else if waiting {
+                    RichText::new("Stop Waiting").size(18.0)
                 } else if self.is_fishing {
                     if let Some(start_time) = self.start_time {
-                        let elapsed = start_time.elapsed().as_secs() as i32;
-                        if elapsed < 5 {
-                            self.countdown = 5 - elapsed;
+                        let delay = self.config.initial_delay();
+                        let elapsed = start_time.elapsed();
+                        if elapsed < delay {
+                            self.countdown = (delay - elapsed).as_secs_f32().ceil() as i32;
                             RichText::new(format!("Starting in {}s...", self.countdown)).size(18.0)
                         } else {
                             self.start_time = None;
@@ -192,16 +478,33 @@ impl eframe::App for FisherUi {
                 if ui
                     .add_sized([200.0, 40.0], egui::Button::new(button_text))
                     .clicked()
+                    && (self.game_running || self.is_fishing)
                 {
-                    if self.game_running {
-                        if self.is_fishing {
-                            self.stop_fishing();
-                        } else {
-                            self.start_fishing();
-                        }
+                    if self.is_fishing {
+                        self.stop_fishing();
+                    } else {
+                        self.start_fishing();
                     }
                 }
 
+                if waiting {
+                    ui.add_space(10.0);
+                    ui.label(
+                        RichText::new("game exited, waiting for it to restart...")
+                            .size(12.0)
+                            .color(Color32::from_rgb(210, 153, 34)),
+                    );
+                }
+
+                if let Some(error) = &self.error {
+                    ui.add_space(10.0);
+                    ui.label(
+                        RichText::new(error)
+                            .size(12.0)
+                            .color(Color32::from_rgb(248, 81, 73)),
+                    );
+                }
+
                 ui.add_space(25.0);
 
                 ui.group(|ui| {
@@ -249,17 +552,110 @@ impl eframe::App for FisherUi {
                         ui.add_space(15.0);
                         ui.vertical(|ui| {
                             ui.add_space(10.0);
-                            ui.label(RichText::new("Threshold:").size(16.0));
+                            ui.label(RichText::new("Detection:").size(16.0));
+                            let has_hook = self.build.as_ref().is_none_or(|build| build.has_hook);
+                            if !has_hook {
+                                self.config.mode = DetectionMode::RodState;
+                            }
                             ui.add_space(5.0);
                             ui.horizontal(|ui| {
                                 ui.add_space(10.0);
-                                ui.add(egui::Slider::new(&mut self.threshold, 0..=10));
+                                ui.radio_value(
+                                    &mut self.config.mode,
+                                    DetectionMode::RodState,
+                                    RichText::new("Rod state").size(14.0),
+                                );
+                                ui.add_enabled_ui(has_hook, |ui| {
+                                    ui.radio_value(
+                                        &mut self.config.mode,
+                                        DetectionMode::HookDip,
+                                        RichText::new("Hook dip").size(14.0),
+                                    )
+                                    .on_disabled_hover_text(
+                                        "the offsets for this build have no hook table",
+                                    );
+                                });
                             });
                             ui.add_space(10.0);
+                            match self.config.mode {
+                                DetectionMode::RodState => self.show_detector_settings(ui),
+                                DetectionMode::HookDip => slider(
+                                    ui,
+                                    &mut self.config.hook_reel_delay_ms,
+                                    fisher::HOOK_REEL_DELAY_RANGE,
+                                    "reel delay (ms)",
+                                ),
+                            }
+                            ui.add_space(5.0);
                             ui.horizontal(|ui| {
                                 ui.add_space(10.0);
-                                ui.label(
-                                    RichText::new("The delay before reeling the rod in").size(12.0),
+                                ui.add_enabled(
+                                    !self.is_fishing,
+                                    egui::Checkbox::new(
+                                        &mut self.record,
+                                        RichText::new("Record a trace for replay").size(14.0),
+                                    ),
+                                );
+                            });
+                            ui.add_space(15.0);
+                        });
+                    });
+                });
+
+                ui.add_space(20.0);
+
+                ui.group(|ui| {
+                    ui.set_width(300.0);
+                    ui.horizontal(|ui| {
+                        ui.add_space(15.0);
+                        ui.vertical(|ui| {
+                            ui.add_space(10.0);
+                            ui.label(RichText::new("Process:").size(16.0));
+                            ui.add_space(5.0);
+                            ui.horizontal(|ui| {
+                                ui.add_space(10.0);
+                                ui.add_enabled(
+                                    !self.is_fishing,
+                                    egui::TextEdit::singleline(&mut self.process_name)
+                                        .desired_width(240.0),
+                                );
+                            });
+
+                            if self.processes.len() > 1 {
+                                ui.add_space(5.0);
+                                ui.horizontal(|ui| {
+                                    ui.add_space(10.0);
+                                    let selected = self
+                                        .processes
+                                        .iter()
+                                        .find(|process| Some(process.pid) == self.selected_pid)
+                                        .map(describe_process)
+                                        .unwrap_or_default();
+                                    ui.add_enabled_ui(!self.is_fishing, |ui| {
+                                        egui::ComboBox::from_id_source("instance")
+                                            .width(240.0)
+                                            .selected_text(selected)
+                                            .show_ui(ui, |ui| {
+                                                for process in &self.processes {
+                                                    ui.selectable_value(
+                                                        &mut self.selected_pid,
+                                                        Some(process.pid),
+                                                        describe_process(process),
+                                                    );
+                                                }
+                                            });
+                                    });
+                                });
+                            }
+                            ui.add_space(5.0);
+                            ui.horizontal(|ui| {
+                                ui.add_space(10.0);
+                                ui.add_enabled(
+                                    !self.is_fishing,
+                                    egui::Checkbox::new(
+                                        &mut self.resume,
+                                        RichText::new("Resume when the game restarts").size(14.0),
+                                    ),
                                 );
                             });
                             ui.add_space(15.0);
@@ -272,14 +668,81 @@ impl eframe::App for FisherUi {
                 ui.with_layout(egui::Layout::bottom_up(egui::Align::Center), |ui| {
                     ui.add_space(10.0);
                     ui.label(RichText::new(format!("version {}", self.version)).size(12.0));
+                    if ui.small_button("Diagnostics").clicked() {
+                        self.diagnostics.open = true;
+                    }
+                    if ui.small_button("Timings").clicked() {
+                        self.timings_open = true;
+                    }
                 });
             });
         });
 
+        self.diagnostics.show(ctx, self.selected_pid);
+        self.show_timings(ctx);
+
         ctx.request_repaint();
     }
 }
 
+fn describe_process(process: &ProcessInfo) -> String {
+    let mut description = format!("pid {}", process.pid);
+    if let Some(title) = &process.window_title {
+        description.push_str(&format!(" - {}", title));
+    }
+    if let Some(age) = process
+        .start_time
+        .and_then(|start| SystemTime::now().duration_since(start).ok())
+    {
+        description.push_str(&format!(" - started {}m ago", age.as_secs() / 60));
+    }
+    description
+}
+
+impl BuildCheck {
+    fn run(pid: u32) -> Self {
+        let (build, entry) = read_build(pid).unwrap_or_default();
+        BuildCheck {
+            pid,
+            version: build.version,
+            tested: entry.as_ref().is_some_and(|entry| !entry.is_fallback()),
+            has_hook: entry.as_ref().is_some_and(|entry| entry.hook.is_some()),
+            offsets: entry.map(|entry| entry.name),
+        }
+    }
+}
+
+fn read_build(pid: u32) -> Option<(GameBuild, Option<OffsetEntry>)> {
+    let database = OffsetDatabase::load().ok()?;
+    let memory = MemoryReader::new(pid).ok()?;
+    let module = memory.find_module(memory::DEFAULT_MODULE).ok()?;
+    let build = GameBuild::read(&memory, &module);
+    let entry = database.select(memory::DEFAULT_MODULE, &build).cloned();
+    Some((build, entry))
+}
+
+fn slider<T: egui::emath::Numeric>(
+    ui: &mut egui::Ui,
+    value: &mut T,
+    range: std::ops::RangeInclusive<T>,
+    text: &str,
+) {
+    ui.horizontal(|ui| {
+        ui.add_space(10.0);
+        ui.add(egui::Slider::new(value, range).text(RichText::new(text).size(14.0)));
+    });
+}
+
+fn load_config() -> Result<FisherConfig, String> {
+    let path = fisher::config_path();
+    if !path.exists() {
+        return Ok(FisherConfig::default());
+    }
+    let config = FisherConfig::load(&path)?;
+    println!("loaded settings from {}", path.display());
+    Ok(config)
+}
+
 fn load_icon(path: &str) -> Option<egui::IconData> {
     ImageReader::open(path)
         .ok()